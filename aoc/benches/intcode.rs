@@ -0,0 +1,464 @@
+//! Run the intcode programs of all days on every intcode engine.
+//!
+//! Run with `cargo bench -p aoc --bench intcode [-- --json path]`. Each program gets a fixed
+//! input; programs that would wait for more input stop there, which compiled code cannot do,
+//! so the JIT only runs the programs that halt.
+//!
+//! The intcode2 interpreter wraps around on overflow, like the JIT; the `checked` and `bigint`
+//! engines show where that changes the results. The `iocached` engine replays the output of
+//! inputs it has seen in the same state, and the cache outlives a run, so it mostly measures
+//! the cache lookups after the first iteration.
+//!
+//! The object file backend (`intcode_aot`) is left out: its code has to be linked in at build
+//! time, and it is built by the same `intcode_jit::Compiler` as the JIT's, differing only in
+//! position independent calls into the runtime, so the `jit` rows stand in for it.
+//! `cargo run --release -p day09` times the AOT build of day 9's BOOST program against the
+//! interpreter.
+
+use common::intcode;
+use common::intcode2::{Checked, Computable, Computer, ComputerImpl, Hooks, WhatsUp};
+use common::intcode_decompile::compile;
+use common::intcode_runtime::IoContext;
+use common::runner::{Day, DefaultInput};
+use num::BigInt;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Measure each engine for at least this long.
+const MEASURE_TIME: Duration = Duration::from_millis(200);
+
+/// Tracks the current and peak heap usage.
+struct CountingAlloc;
+
+static HEAP: AtomicUsize = AtomicUsize::new(0);
+static PEAK_HEAP: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let heap = HEAP.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_HEAP.fetch_max(heap, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        HEAP.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// How much more heap than before `f` was in use at the peak while `f` ran.
+fn peak_heap<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = HEAP.load(Ordering::Relaxed);
+    PEAK_HEAP.store(before, Ordering::Relaxed);
+    let result = f();
+    (result, PEAK_HEAP.load(Ordering::Relaxed) - before)
+}
+
+/// A day's program with a fixed input.
+struct Case {
+    day: &'static Day,
+    program: Vec<i64>,
+    input: Vec<i64>,
+}
+
+impl Case {
+    fn new(day: &'static Day, input: Vec<i64>) -> Self {
+        let program = match day.input {
+            DefaultInput::Intcode(program) => program.to_vec(),
+            _ => panic!("day {} has no intcode program", day.number),
+        };
+        Case {
+            day,
+            program,
+            input,
+        }
+    }
+
+    fn patch(mut self, address: usize, value: i64) -> Self {
+        self.program[address] = value;
+        self
+    }
+}
+
+fn ascii(text: &str) -> Vec<i64> {
+    text.bytes().map(i64::from).collect()
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case::new(&day02::DAY, vec![]).patch(1, 12).patch(2, 2),
+        Case::new(&day05::DAY, vec![5]),
+        Case::new(&day07::DAY, vec![0, 0]),
+        Case::new(&day09::DAY, vec![2]),
+        Case::new(&day11::DAY, vec![0; 20_000]),
+        // free play: the ball is lost if the joystick never moves
+        Case::new(&day13::DAY, vec![0; 20_000]).patch(0, 2),
+        Case::new(&day15::DAY, [1, 4, 2, 3].repeat(250)),
+        Case::new(&day17::DAY, vec![]),
+        Case::new(&day19::DAY, vec![20, 30]),
+        Case::new(
+            &day21::DAY,
+            ascii("NOT A J\nNOT B T\nOR T J\nNOT C T\nOR T J\nAND D J\nWALK\n"),
+        ),
+        Case::new(&day23::DAY, [vec![0], vec![-1; 1_000]].concat()),
+        Case::new(&day25::DAY, ascii(&"inv\n".repeat(20))),
+    ]
+}
+
+/// How a run ended, and the output up to there.
+#[derive(Debug, PartialEq)]
+enum Run {
+    Halted(Vec<i64>),
+    NeedInput(Vec<i64>),
+}
+
+/// Runs a case once; the setup (e.g. compilation) is not measured with the run.
+type Runner = Box<dyn Fn() -> Result<Run, String>>;
+
+struct Engine {
+    name: &'static str,
+
+    /// Can stop when the input runs out.
+    suspends: bool,
+    setup: fn(&Case) -> Runner,
+}
+
+const ENGINES: [Engine; 6] = [
+    Engine {
+        name: "intcode",
+        suspends: true,
+        setup: |case| setup_intcode(case, IntcodeVm::step),
+    },
+    Engine {
+        name: "iocached",
+        suspends: true,
+        setup: |case| setup_intcode(case, IntcodeVm::step_iocached),
+    },
+    Engine {
+        name: "intcode2",
+        suspends: true,
+        setup: setup_intcode2::<i64>,
+    },
+    Engine {
+        name: "checked",
+        suspends: true,
+        setup: setup_intcode2::<Checked>,
+    },
+    Engine {
+        name: "bigint",
+        suspends: true,
+        setup: setup_intcode2::<BigInt>,
+    },
+    Engine {
+        name: "jit",
+        suspends: false,
+        setup: setup_jit,
+    },
+];
+
+type IntcodeVm = intcode::IoComputer<std::vec::IntoIter<i64>, Vec<i64>>;
+
+fn setup_intcode(case: &Case, step: fn(&mut IntcodeVm) -> Option<bool>) -> Runner {
+    let program = case.program.clone();
+    let input = case.input.clone();
+    Box::new(move || {
+        let mut vm = IntcodeVm::with_io(&program, input.clone().into_iter(), vec![]);
+        loop {
+            if vm.input.len() == 0 {
+                match vm.peek() {
+                    Some((intcode::Op::Inp(_), _)) => return Ok(Run::NeedInput(vm.output)),
+                    None => return Err(format!("unsupported instruction at pc {}", vm.pc)),
+                    _ => {}
+                }
+            }
+            match step(&mut vm) {
+                Some(true) => {}
+                Some(false) => return Ok(Run::Halted(vm.output)),
+                None => return Err(format!("unsupported instruction at pc {}", vm.pc)),
+            }
+        }
+    })
+}
+
+fn run_intcode2<T: Computable, H: Hooks>(
+    vm: &mut ComputerImpl<T, H>,
+    input: &[i64],
+) -> Result<Run, String> {
+    vm.push_input(input.iter().cloned().map(T::from));
+    let mut output = vec![];
+    loop {
+        match vm.run(None) {
+            Some(WhatsUp::Output(x)) => output.push(x.as_i64()),
+            Some(WhatsUp::Halt) => return Ok(Run::Halted(output)),
+            Some(WhatsUp::NeedInput) => return Ok(Run::NeedInput(output)),
+            Some(WhatsUp::Fault(f)) => return Err(f.to_string()),
+            Some(WhatsUp::BudgetExhausted) | Some(WhatsUp::InfiniteLoop) => unreachable!(),
+            None => return Err(format!("invalid instruction at pc {}", vm.pc)),
+        }
+    }
+}
+
+fn setup_intcode2<T: Computable + 'static>(case: &Case) -> Runner {
+    let program = case.program.clone();
+    let input = case.input.clone();
+    Box::new(move || run_intcode2(&mut ComputerImpl::<T>::new(&program), &input))
+}
+
+fn setup_jit(case: &Case) -> Runner {
+    let compiled = compile(&case.program);
+    let program = case.program.clone();
+    let input = case.input.clone();
+    Box::new(move || {
+        let mut vm = Computer::new(&program);
+        vm.push_input(input.iter().cloned());
+        match unsafe { IoContext::new(&mut vm).run(compiled) } {
+            Ok(output) => Ok(Run::Halted(output)),
+            Err(f) => Err(f.to_string()),
+        }
+    })
+}
+
+/// Counts executed instructions.
+#[derive(Default)]
+struct InstructionCount(u64);
+
+impl Hooks for InstructionCount {
+    fn mem_fetch(&mut self, _addr: usize) {
+        self.0 += 1;
+    }
+    fn mem_read(&mut self, _addr: usize) {}
+    fn mem_write(&mut self, _addr: usize) {}
+}
+
+/// The result of running one case on one engine.
+struct Measurement {
+    day: u32,
+    engine: &'static str,
+    instructions: u64,
+    setup: Duration,
+    iterations: u32,
+    total: Duration,
+    peak_heap: usize,
+    error: Option<String>,
+}
+
+impl Measurement {
+    fn per_run(&self) -> Duration {
+        self.total / self.iterations.max(1)
+    }
+
+    fn instructions_per_second(&self) -> f64 {
+        if self.total == Duration::default() {
+            return 0.0;
+        }
+        (self.instructions * u64::from(self.iterations)) as f64 / self.total.as_secs_f64()
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"day\": {}, \"engine\": {}, \"instructions\": {}, \"setup_ns\": {}, \
+             \"iterations\": {}, \"total_ns\": {}, \"ns_per_run\": {}, \
+             \"instructions_per_second\": {:.0}, \"peak_heap_bytes\": {}, \"error\": {}}}",
+            self.day,
+            json_string(self.engine),
+            self.instructions,
+            self.setup.as_nanos(),
+            self.iterations,
+            self.total.as_nanos(),
+            self.per_run().as_nanos(),
+            self.instructions_per_second(),
+            self.peak_heap,
+            self.error
+                .as_deref()
+                .map_or("null".to_string(), json_string),
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json + "\""
+}
+
+impl Measurement {
+    fn new(case: &Case, engine: &Engine, instructions: u64) -> Self {
+        Measurement {
+            day: case.day.number,
+            engine: engine.name,
+            instructions,
+            setup: Duration::default(),
+            iterations: 0,
+            total: Duration::default(),
+            peak_heap: 0,
+            error: None,
+        }
+    }
+
+    fn failed(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    fn row(&self) -> String {
+        match &self.error {
+            None => format!(
+                "{:>3}  {:<8}  {:>12}  {:>10.1?}  {:>10.1?}  {:>10.1}  {:>8} kB",
+                self.day,
+                self.engine,
+                self.instructions,
+                self.setup,
+                self.per_run(),
+                self.instructions_per_second() / 1e6,
+                self.peak_heap / 1024
+            ),
+            Some(e) => format!("{:>3}  {:<8}  {}", self.day, self.engine, e),
+        }
+    }
+}
+
+/// Run a case on the intcode2 interpreter and count the instructions.
+fn reference_run(case: &Case) -> (Run, u64) {
+    let mut vm = ComputerImpl::<i64, InstructionCount>::new(&case.program);
+    let reference = run_intcode2(&mut vm, &case.input).expect("reference run failed");
+    let instructions = vm.hooks.borrow().0;
+    (reference, instructions)
+}
+
+fn measure(case: &Case, engine: &Engine, min_time: Duration) -> Measurement {
+    let (reference, instructions) = reference_run(case);
+    let mut m = Measurement::new(case, engine, instructions);
+
+    let start = Instant::now();
+    let run = (engine.setup)(case);
+    m.setup = start.elapsed();
+
+    while m.iterations == 0 || m.total < min_time {
+        let start = Instant::now();
+        let (result, heap) = peak_heap(&run);
+        m.total += start.elapsed();
+        m.iterations += 1;
+        m.peak_heap = m.peak_heap.max(heap);
+
+        match result {
+            Ok(ref result) if *result == reference => {}
+            Ok(_) => return m.failed("the output differs from intcode2"),
+            Err(e) => return m.failed(e),
+        }
+    }
+    m
+}
+
+/// Measure in a child process, so that the compiled code of one engine crashing does not take
+/// down the whole benchmark. Returns the table row and the JSON object.
+fn measure_isolated(case: &Case, engine: &Engine, bench: bool) -> (String, String) {
+    let (reference, instructions) = reference_run(case);
+    let m = Measurement::new(case, engine, instructions);
+    if !engine.suspends {
+        if let Run::NeedInput(_) = reference {
+            let m = m.failed("the program waits for more input");
+            return (m.row(), m.to_json());
+        }
+    }
+
+    let mut child = std::process::Command::new(std::env::current_exe().unwrap());
+    child.args(["--case", &case.day.number.to_string(), engine.name]);
+    if bench {
+        child.arg("--bench");
+    }
+    let m = match child.output() {
+        Ok(output) if output.status.success() => {
+            // the result is printed last; the JIT prints diagnostics
+            let output = String::from_utf8_lossy(&output.stdout);
+            let mut lines = output.lines().rev();
+            if let (Some(json), Some(row)) = (lines.next(), lines.next()) {
+                return (row.to_string(), json.to_string());
+            }
+            m.failed("no result")
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // the panic message follows the line saying where the panic happened
+            let message = stderr
+                .lines()
+                .skip_while(|line| !line.contains("panicked at"))
+                .nth(1);
+            match message {
+                Some(message) => m.failed(format!("crashed ({}): {}", output.status, message)),
+                None => m.failed(format!("crashed ({})", output.status)),
+            }
+        }
+        Err(e) => m.failed(format!("cannot run the benchmark: {}", e)),
+    };
+    (m.row(), m.to_json())
+}
+
+fn commit() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let arg = |name: &str| args.iter().position(|arg| arg == name);
+    // `cargo test` runs benches once as a smoke test
+    let bench = arg("--bench").is_some();
+
+    if let Some(i) = arg("--case") {
+        let day: u32 = args[i + 1].parse().unwrap();
+        let case = cases().into_iter().find(|c| c.day.number == day).unwrap();
+        let engine = ENGINES.iter().find(|e| e.name == args[i + 2]).unwrap();
+        let min_time = if bench {
+            MEASURE_TIME
+        } else {
+            Duration::default()
+        };
+        let m = measure(&case, engine, min_time);
+        println!("{}\n{}", m.row(), m.to_json());
+        return;
+    }
+
+    println!(
+        "{:>3}  {:<8}  {:>12}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "day", "engine", "instructions", "setup", "per run", "Minstr/s", "heap"
+    );
+    let mut results = vec![];
+    for case in cases() {
+        for engine in &ENGINES {
+            let (row, json) = measure_isolated(&case, engine, bench);
+            println!("{}", row);
+            results.push(format!("    {}", json));
+        }
+    }
+
+    if let Some(i) = arg("--json") {
+        let path = args.get(i + 1).expect("--json needs a path");
+        let json = format!(
+            "{{\n  \"commit\": {},\n  \"results\": [\n{}\n  ]\n}}\n",
+            commit().as_deref().map_or("null".to_string(), json_string),
+            results.join(",\n")
+        );
+        std::fs::write(path, json).expect("writing the results failed");
+        println!("\nresults written to {}", path);
+    }
+}
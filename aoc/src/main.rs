@@ -0,0 +1,390 @@
+use common::input::Source;
+use common::regression::{Answers, Mismatch};
+use common::runner::Day;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Duration;
+
+const DAYS: [&Day; 25] = [
+    &day01::DAY,
+    &day02::DAY,
+    &day03::DAY,
+    &day04::DAY,
+    &day05::DAY,
+    &day06::DAY,
+    &day07::DAY,
+    &day08::DAY,
+    &day09::DAY,
+    &day10::DAY,
+    &day11::DAY,
+    &day12::DAY,
+    &day13::DAY,
+    &day14::DAY,
+    &day15::DAY,
+    &day16::DAY,
+    &day17::DAY,
+    &day18::DAY,
+    &day19::DAY,
+    &day20::DAY,
+    &day21::DAY,
+    &day22::DAY,
+    &day23::DAY,
+    &day24::DAY,
+    &day25::DAY,
+];
+
+/// Known-good answers.
+const ANSWERS: &str = include_str!("../answers.txt");
+
+const USAGE: &str = "usage: aoc run [day] [part] [--input path] [--check]
+
+Solve all days, or one day, or one part of one day.
+--input reads the input from `path` instead of the data directory; - reads stdin.
+--check compares the answers with those recorded in aoc/answers.txt.";
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Args {
+    day: Option<u32>,
+    part: Option<u32>,
+    input: Option<String>,
+    check: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut args = args.iter();
+    match args.next().map(String::as_str) {
+        Some("run") => {}
+        Some(cmd) => return Err(format!("unknown command: {}", cmd)),
+        None => return Err("missing command".to_string()),
+    }
+
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        if arg == "--input" {
+            let path = args.next().ok_or("--input needs a path")?;
+            parsed.input = Some(path.clone());
+            continue;
+        }
+        if arg == "--check" {
+            parsed.check = true;
+            continue;
+        }
+        let n: u32 = arg
+            .parse()
+            .map_err(|_| format!("unexpected argument: {}", arg))?;
+        if parsed.day.is_none() {
+            if !(1..=DAYS.len() as u32).contains(&n) {
+                return Err(format!("there is no day {}", n));
+            }
+            parsed.day = Some(n);
+        } else if parsed.part.is_none() {
+            if !(1..=2).contains(&n) {
+                return Err(format!("there is no part {}", n));
+            }
+            parsed.part = Some(n);
+        } else {
+            return Err(format!("unexpected argument: {}", arg));
+        }
+    }
+
+    if parsed.input.is_some() && parsed.day.is_none() {
+        return Err("--input needs a day".to_string());
+    }
+    Ok(parsed)
+}
+
+/// The outcome of solving one part.
+enum Outcome {
+    Solved(String, Duration),
+    Panicked,
+    NoInput,
+    Skipped,
+}
+
+impl Outcome {
+    fn time(&self) -> Option<Duration> {
+        match self {
+            Outcome::Solved(_, t) => Some(*t),
+            _ => None,
+        }
+    }
+}
+
+fn run_day(day: &Day, parts: &[u32], source: &Source) -> Vec<Outcome> {
+    let input = match day.load(source) {
+        Ok(input) => input,
+        Err(e) => {
+            println!("Day {:2}: {}", day.number, e);
+            return parts.iter().map(|_| Outcome::NoInput).collect();
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            if day.part(part).is_none() {
+                return Outcome::Skipped;
+            }
+            match catch_unwind(AssertUnwindSafe(|| day.solve(part, &input).unwrap())) {
+                Ok((answer, time)) => {
+                    println!(
+                        "Day {:2} part {}: {:>20}  {:>12}",
+                        day.number,
+                        part,
+                        answer,
+                        format_duration(time)
+                    );
+                    Outcome::Solved(answer.to_string(), time)
+                }
+                Err(_) => {
+                    println!("Day {:2} part {}: panicked", day.number, part);
+                    Outcome::Panicked
+                }
+            }
+        })
+        .collect()
+}
+
+fn format_duration(t: Duration) -> String {
+    match t.as_micros() {
+        us if us < 1_000 => format!("{} µs", us),
+        us if us < 1_000_000 => format!("{:.1} ms", us as f64 / 1e3),
+        us => format!("{:.2} s", us as f64 / 1e6),
+    }
+}
+
+fn cell(outcome: &Outcome) -> (String, String) {
+    match outcome {
+        // multi-line answers are images that could not be read
+        Outcome::Solved(answer, time) => (
+            answer.lines().next().unwrap_or("").to_string(),
+            format_duration(*time),
+        ),
+        Outcome::Panicked => ("panicked".to_string(), String::new()),
+        Outcome::NoInput => ("no input".to_string(), String::new()),
+        Outcome::Skipped => ("-".to_string(), String::new()),
+    }
+}
+
+fn count_failures(results: &[(u32, Vec<Outcome>)]) -> usize {
+    results
+        .iter()
+        .flat_map(|(_, outcomes)| outcomes)
+        .filter(|o| matches!(o, Outcome::Panicked | Outcome::NoInput))
+        .count()
+}
+
+/// The solved parts whose answers differ from the recorded ones, and the number of solved parts
+/// without a recorded answer.
+fn check_answers(
+    answers: &Answers,
+    parts: &[u32],
+    results: &[(u32, Vec<Outcome>)],
+) -> (Vec<Mismatch>, usize) {
+    let mut mismatches = vec![];
+    let mut unchecked = 0;
+    for (day, outcomes) in results {
+        for (&part, outcome) in parts.iter().zip(outcomes) {
+            if let Outcome::Solved(answer, _) = outcome {
+                if answers.get(*day, part).is_none() {
+                    unchecked += 1;
+                }
+                mismatches.extend(answers.compare(*day, part, answer));
+            }
+        }
+    }
+    (mismatches, unchecked)
+}
+
+fn print_summary(parts: &[u32], results: &[(u32, Vec<Outcome>)]) {
+    print!("\n{:>3}", "day");
+    for part in parts {
+        print!("  {:>20}  {:>10}", format!("part {}", part), "time");
+    }
+    println!();
+
+    let mut total = Duration::default();
+    for (day, outcomes) in results {
+        print!("{:>3}", day);
+        for outcome in outcomes {
+            let (answer, time) = cell(outcome);
+            print!("  {:>20}  {:>10}", answer, time);
+            total += outcome.time().unwrap_or_default();
+        }
+        println!();
+    }
+
+    let failed = count_failures(results);
+    println!("\ntotal time: {}", format_duration(total));
+    if failed > 0 {
+        println!("{} part(s) failed", failed);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let days: Vec<&Day> = match args.day {
+        Some(n) => vec![DAYS[n as usize - 1]],
+        None => DAYS.to_vec(),
+    };
+    let parts = match args.part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let source = Source::from_arg(args.input.as_deref());
+
+    let results: Vec<_> = days
+        .iter()
+        .map(|day| (day.number, run_day(day, &parts, &source)))
+        .collect();
+
+    print_summary(&parts, &results);
+
+    let mut failed = count_failures(&results) > 0;
+    if args.check {
+        let answers = Answers::parse(ANSWERS).expect("aoc/answers.txt");
+        let (mismatches, unchecked) = check_answers(&answers, &parts, &results);
+        if unchecked > 0 {
+            println!("{} answer(s) not recorded", unchecked);
+        }
+        if mismatches.is_empty() {
+            println!("all recorded answers match");
+        } else {
+            println!(
+                "\n{} answer(s) differ from the recorded ones:",
+                mismatches.len()
+            );
+            for mismatch in &mismatches {
+                print!("{}", mismatch);
+            }
+            failed = true;
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<Args, String> {
+        let args: Vec<String> = s.split_whitespace().map(str::to_string).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn command_line() {
+        assert_eq!(args("run"), Ok(Args::default()));
+        assert_eq!(
+            args("run 7 2"),
+            Ok(Args {
+                day: Some(7),
+                part: Some(2),
+                ..Args::default()
+            })
+        );
+        assert_eq!(
+            args("run 3 --input in.txt"),
+            Ok(Args {
+                day: Some(3),
+                input: Some("in.txt".to_string()),
+                ..Args::default()
+            })
+        );
+        assert_eq!(
+            args("run --check 5"),
+            Ok(Args {
+                day: Some(5),
+                check: true,
+                ..Args::default()
+            })
+        );
+        assert!(args("").is_err());
+        assert!(args("walk").is_err());
+        assert!(args("run 26").is_err());
+        assert!(args("run 1 3").is_err());
+        assert!(args("run 1 2 3").is_err());
+        assert!(args("run --input in.txt").is_err());
+        assert!(args("run 1 --input").is_err());
+    }
+
+    #[test]
+    fn days_are_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.number, i as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn recorded_answers() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        for day in answers.days() {
+            let day = DAYS[day as usize - 1];
+            assert!(answers.get(day.number, 1).is_some());
+            if day.part2.is_none() {
+                assert!(answers.get(day.number, 2).is_none());
+            }
+        }
+
+        let results = vec![(
+            9,
+            vec![
+                Outcome::Solved("2465411646".to_string(), Duration::default()),
+                Outcome::Solved("69782".to_string(), Duration::default()),
+            ],
+        )];
+        let (mismatches, unchecked) = check_answers(&answers, &[1, 2], &results);
+        assert_eq!(unchecked, 0);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "day 9 part 2:\n- 69781\n+ 69782\n"
+        );
+    }
+
+    /// Solve the recorded parts of day `n` and compare them with the recorded answers.
+    fn check_day(n: u32) {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let day = DAYS[n as usize - 1];
+        let input = match day.load(&Source::Default) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("skipping day {}: {}", n, e);
+                return;
+            }
+        };
+        let slow = std::env::var_os("AOC_SLOW").is_some();
+        let mismatches = answers.check(day, &input, slow);
+        let diff: String = mismatches.iter().map(Mismatch::to_string).collect();
+        assert!(mismatches.is_empty(), "\n{}", diff);
+    }
+
+    macro_rules! regression_tests {
+        ($($name:ident: $day:expr),*) => {
+            mod regression {
+                $(
+                    #[test]
+                    fn $name() {
+                        super::check_day($day);
+                    }
+                )*
+            }
+        };
+    }
+
+    regression_tests!(
+        day01: 1, day02: 2, day03: 3, day04: 4, day05: 5, day06: 6, day07: 7, day08: 8, day09: 9,
+        day10: 10, day11: 11, day12: 12, day13: 13, day14: 14, day15: 15, day16: 16, day17: 17,
+        day18: 18, day19: 19, day20: 20, day21: 21, day22: 22, day23: 23, day24: 24, day25: 25
+    );
+}
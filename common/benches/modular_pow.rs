@@ -0,0 +1,53 @@
+//! Compare `ModularValue::pow` on day 22's parameters for `i64` and `i128`.
+//!
+//! Run with `cargo bench -p common --bench modular_pow`. Fails if `i64` is slower than `i128`
+//! by more than `TOLERANCE`.
+
+use common::ModularValue;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const DECK_SIZE: i64 = 119315717514047;
+const REPETITIONS: i64 = 101741582076661;
+
+/// Timings of the same code vary by a few percent between runs.
+const TOLERANCE: f64 = 1.1;
+
+fn main() {
+    // `cargo test` runs benches once as a smoke test
+    let bench = std::env::args().any(|arg| arg == "--bench");
+    let iterations = if bench { 100_000 } else { 1 };
+
+    let base = 68718952447;
+
+    let (narrow, t64) = measure(iterations, || {
+        ModularValue::new(black_box(base), DECK_SIZE).pow(black_box(REPETITIONS))
+    });
+    let (wide, t128) = measure(iterations, || {
+        ModularValue::new(black_box(base as i128), DECK_SIZE as i128)
+            .pow(black_box(REPETITIONS as i128))
+    });
+
+    assert_eq!(narrow.to_string(), wide.to_string());
+
+    println!("pow i64:  {:?} per call", t64);
+    println!("pow i128: {:?} per call", t128);
+
+    let ratio = t64.as_secs_f64() / t128.as_secs_f64();
+    if bench && ratio > TOLERANCE {
+        eprintln!(
+            "regression: pow i64 takes {:.2} times as long as i128",
+            ratio
+        );
+        std::process::exit(1);
+    }
+}
+
+fn measure<T>(iterations: u32, mut f: impl FnMut() -> T) -> (T, Duration) {
+    let mut result = f();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        result = black_box(f());
+    }
+    (result, start.elapsed() / iterations)
+}
@@ -0,0 +1,170 @@
+use crate::{ModularValue, MulMod};
+use num::{Num, Signed};
+use std::fmt::Debug;
+use std::ops::{BitAnd, Shr};
+
+/// The map `x -> a * x + b (mod m)`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct AffineMod<T> {
+    a: ModularValue<T>,
+    b: ModularValue<T>,
+}
+
+impl<T> AffineMod<T>
+where
+    T: Debug + Copy + Num + PartialOrd + MulMod,
+{
+    pub fn new(a: T, b: T, modulo: T) -> Self {
+        AffineMod {
+            a: ModularValue::new(a, modulo),
+            b: ModularValue::new(b, modulo),
+        }
+    }
+
+    pub fn identity(modulo: T) -> Self {
+        Self::new(T::one(), T::zero(), modulo)
+    }
+
+    /// `x -> x + b`
+    pub fn offset(b: T, modulo: T) -> Self {
+        Self::new(T::one(), b, modulo)
+    }
+
+    /// `x -> a * x`
+    pub fn scale(a: T, modulo: T) -> Self {
+        Self::new(a, T::zero(), modulo)
+    }
+
+    pub fn multiplier(&self) -> ModularValue<T> {
+        self.a
+    }
+
+    pub fn increment(&self) -> ModularValue<T> {
+        self.b
+    }
+
+    pub fn apply(&self, x: T) -> ModularValue<T> {
+        self.a * x + self.b
+    }
+
+    /// The map that applies `self` first and `next` afterwards.
+    pub fn then(self, next: Self) -> Self {
+        AffineMod {
+            a: next.a * self.a,
+            b: next.a * self.b + next.b,
+        }
+    }
+}
+
+impl<T> AffineMod<T>
+where
+    T: Debug + Copy + Num + PartialOrd + MulMod + Signed,
+{
+    /// The inverse map; exists iff `a` is invertible.
+    pub fn inv(self) -> Option<Self> {
+        let a = self.a.inv()?;
+        Some(AffineMod {
+            a,
+            b: ModularValue::new(T::zero(), self.b.modulo) - a * self.b,
+        })
+    }
+}
+
+impl<T> AffineMod<T>
+where
+    T: Debug + Copy + Num + PartialOrd + MulMod + BitAnd<Output = T> + Shr<Output = T>,
+{
+    /// Apply the map `n` times.
+    ///
+    /// Squares the map itself instead of summing the geometric series `1 + a + a^2 + ...`,
+    /// so `a - 1` need not be invertible.
+    pub fn pow(self, n: T) -> Self {
+        let mut f = self;
+        let mut n = n;
+        let mut r = Self::identity(self.a.modulo);
+        while n > T::zero() {
+            if (n & T::one()) == T::one() {
+                r = r.then(f);
+            }
+            n = n >> T::one();
+            f = f.then(f);
+        }
+        r
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for AffineMod<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "x -> {} * x + {} (mod {})",
+            self.a.value, self.b.value, self.a.modulo
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(f: &AffineMod<i64>, x: i64, n: i64) -> ModularValue<i64> {
+        let mut x = ModularValue::new(x, f.a.modulo);
+        for _ in 0..n {
+            x = f.apply(x.value);
+        }
+        x
+    }
+
+    #[test]
+    fn composition() {
+        let f = AffineMod::new(3, 4, 11);
+        let g = AffineMod::new(5, -2, 11);
+        for x in 0..11 {
+            assert_eq!(f.then(g).apply(x), g.apply(f.apply(x).value));
+            assert_eq!(f.then(AffineMod::identity(11)).apply(x), f.apply(x));
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let f = AffineMod::new(7, 3, 10);
+        let g = f.inv().unwrap();
+        for x in 0..10 {
+            assert_eq!(g.apply(f.apply(x).value), x);
+            assert_eq!(f.apply(g.apply(x).value), x);
+        }
+        assert_eq!(AffineMod::new(4, 3, 10).inv(), None);
+    }
+
+    #[test]
+    fn pow_matches_repeated_application() {
+        for m in 1..20 {
+            for a in 0..m {
+                for b in 0..m {
+                    let f = AffineMod::new(a, b, m);
+                    for n in 0..25 {
+                        assert_eq!(f.pow(n).apply(1), brute_force(&f, 1, n), "{} ^ {}", f, n);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pow_without_invertible_series() {
+        // a - 1 == 0 has no inverse; the map is a plain offset
+        let f = AffineMod::offset(3, 119315717514047i64);
+        assert_eq!(
+            f.pow(101741582076661).apply(0),
+            3 * 101741582076661 % 119315717514047
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            AffineMod::new(-1, 12, 10).to_string(),
+            "x -> 9 * x + 2 (mod 10)"
+        );
+    }
+}
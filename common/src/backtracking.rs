@@ -1,3 +1,10 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 pub trait BackTracking {
     type PartialCandidate: std::fmt::Debug;
 
@@ -12,8 +19,25 @@ pub trait BackTracking {
         c: &Self::PartialCandidate,
     ) -> Box<dyn Iterator<Item = Self::PartialCandidate>>;
 
-    fn backtrack(&mut self) {
-        self.backtrack_recursive(&self.root())
+    /// `reject` and `accept` in one go.
+    ///
+    /// All `Search` drivers call this instead of `reject` and `accept`. Override it if both
+    /// share expensive work.
+    fn evaluate(&self, c: &Self::PartialCandidate) -> Verdict {
+        if self.reject(c) {
+            Verdict::Reject
+        } else if self.accept(c) {
+            Verdict::Accept
+        } else {
+            Verdict::Partial
+        }
+    }
+
+    fn backtrack(&mut self) -> SearchStats
+    where
+        Self: Sized,
+    {
+        Search::new().run(self)
     }
 
     fn backtrack_recursive(&mut self, c: &Self::PartialCandidate) {
@@ -30,3 +54,1255 @@ pub trait BackTracking {
         }
     }
 }
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Verdict {
+    /// Neither the candidate nor any of its extensions is a solution.
+    Reject,
+
+    /// The candidate is a solution; its extensions may be too.
+    Accept,
+
+    /// The candidate is no solution, but its extensions may be.
+    Partial,
+}
+
+/// A `BackTracking` problem whose partial candidates can be identified by a key.
+pub trait Transposition: BackTracking {
+    type Key: Hash + Eq + Clone;
+
+    /// Candidates with equal keys must be interchangeable: they get the same verdict, the
+    /// same cost, and their extensions are interchangeable too. Searched with `Cheapest`,
+    /// they may differ in cost.
+    fn key(&self, c: &Self::PartialCandidate) -> Self::Key;
+}
+
+/// Adapter that explores every key of a `Transposition` problem only once.
+///
+/// Later candidates with a known key are rejected without evaluating them, and `accept`
+/// answers from the verdict of the first. Works with all `Search` drivers except
+/// `iterative_deepening`, which must revisit candidates.
+pub struct Memoized<B: Transposition> {
+    problem: B,
+    /// The verdict per key; `None` while the first candidate is being evaluated.
+    verdicts: Mutex<HashMap<B::Key, Option<Verdict>>>,
+    duplicates: AtomicUsize,
+}
+
+impl<B: Transposition> Memoized<B> {
+    pub fn new(problem: B) -> Self {
+        Memoized {
+            problem,
+            verdicts: Mutex::new(HashMap::new()),
+            duplicates: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of candidates skipped because their key was known.
+    pub fn duplicates(&self) -> usize {
+        self.duplicates.load(Ordering::SeqCst)
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.problem
+    }
+
+    pub fn into_inner(self) -> B {
+        self.problem
+    }
+}
+
+impl<B: Transposition> BackTracking for Memoized<B> {
+    type PartialCandidate = B::PartialCandidate;
+
+    fn output(&mut self, c: &Self::PartialCandidate) {
+        self.problem.output(c)
+    }
+
+    fn root(&self) -> Self::PartialCandidate {
+        self.problem.root()
+    }
+
+    fn reject(&self, c: &Self::PartialCandidate) -> bool {
+        self.evaluate(c) == Verdict::Reject
+    }
+
+    fn accept(&self, c: &Self::PartialCandidate) -> bool {
+        let key = self.problem.key(c);
+        if let Some(Some(verdict)) = self.verdicts.lock().unwrap().get(&key) {
+            return *verdict == Verdict::Accept;
+        }
+        self.problem.accept(c)
+    }
+
+    fn extend(
+        &self,
+        c: &Self::PartialCandidate,
+    ) -> Box<dyn Iterator<Item = Self::PartialCandidate>> {
+        self.problem.extend(c)
+    }
+
+    fn evaluate(&self, c: &Self::PartialCandidate) -> Verdict {
+        let key = self.problem.key(c);
+        // claim the key under the same lock, so no other worker evaluates it meanwhile
+        match self.verdicts.lock().unwrap().entry(key.clone()) {
+            Entry::Occupied(_) => {
+                self.duplicates.fetch_add(1, Ordering::SeqCst);
+                return Verdict::Reject;
+            }
+            Entry::Vacant(e) => {
+                e.insert(None);
+            }
+        }
+        let verdict = self.problem.evaluate(c);
+        self.verdicts.lock().unwrap().insert(key, Some(verdict));
+        verdict
+    }
+}
+
+impl<B: Transposition + Optimization> Optimization for Memoized<B> {
+    type Cost = B::Cost;
+
+    fn cost(&self, c: &Self::PartialCandidate) -> Self::Cost {
+        self.problem.cost(c)
+    }
+
+    fn lower_bound(&self, c: &Self::PartialCandidate) -> Self::Cost {
+        self.problem.lower_bound(c)
+    }
+}
+
+/// Adapter that explores every key of a `Transposition` problem only from the cheapest
+/// candidate seen so far.
+///
+/// Candidates with equal keys may differ in cost, like different paths to the same place,
+/// as long as extending the cheaper one never costs more than extending the other the same
+/// way. A candidate is rejected without evaluating it if one with the same key was at
+/// most as expensive. Works with all `Search` drivers except `iterative_deepening`.
+pub struct Cheapest<B: Transposition + Optimization> {
+    problem: B,
+    /// The lowest cost per key so far.
+    costs: Mutex<HashMap<B::Key, B::Cost>>,
+    duplicates: AtomicUsize,
+}
+
+impl<B: Transposition + Optimization> Cheapest<B> {
+    pub fn new(problem: B) -> Self {
+        Cheapest {
+            problem,
+            costs: Mutex::new(HashMap::new()),
+            duplicates: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of candidates skipped because their key was known at a lower or equal cost.
+    pub fn duplicates(&self) -> usize {
+        self.duplicates.load(Ordering::SeqCst)
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.problem
+    }
+
+    pub fn into_inner(self) -> B {
+        self.problem
+    }
+}
+
+impl<B: Transposition + Optimization> BackTracking for Cheapest<B> {
+    type PartialCandidate = B::PartialCandidate;
+
+    fn output(&mut self, c: &Self::PartialCandidate) {
+        self.problem.output(c)
+    }
+
+    fn root(&self) -> Self::PartialCandidate {
+        self.problem.root()
+    }
+
+    fn reject(&self, c: &Self::PartialCandidate) -> bool {
+        self.evaluate(c) == Verdict::Reject
+    }
+
+    fn accept(&self, c: &Self::PartialCandidate) -> bool {
+        self.problem.accept(c)
+    }
+
+    fn extend(
+        &self,
+        c: &Self::PartialCandidate,
+    ) -> Box<dyn Iterator<Item = Self::PartialCandidate>> {
+        self.problem.extend(c)
+    }
+
+    fn evaluate(&self, c: &Self::PartialCandidate) -> Verdict {
+        let cost = self.problem.cost(c);
+        match self.costs.lock().unwrap().entry(self.problem.key(c)) {
+            Entry::Occupied(e) if *e.get() <= cost => {
+                self.duplicates.fetch_add(1, Ordering::SeqCst);
+                return Verdict::Reject;
+            }
+            Entry::Occupied(mut e) => {
+                e.insert(cost);
+            }
+            Entry::Vacant(e) => {
+                e.insert(cost);
+            }
+        }
+        self.problem.evaluate(c)
+    }
+}
+
+impl<B: Transposition + Optimization> Optimization for Cheapest<B> {
+    type Cost = B::Cost;
+
+    fn cost(&self, c: &Self::PartialCandidate) -> Self::Cost {
+        self.problem.cost(c)
+    }
+
+    fn lower_bound(&self, c: &Self::PartialCandidate) -> Self::Cost {
+        self.problem.lower_bound(c)
+    }
+}
+
+/// A `BackTracking` problem that looks for the cheapest solution.
+///
+/// See `Search::branch_and_bound` and `Search::best_first`.
+pub trait Optimization: BackTracking {
+    type Cost: Ord + Copy + std::fmt::Debug;
+
+    /// The cost of an accepted candidate.
+    fn cost(&self, c: &Self::PartialCandidate) -> Self::Cost;
+
+    /// A lower bound for the cost of `c` and of every candidate it can be extended to.
+    ///
+    /// The default assumes extending a candidate never makes it cheaper.
+    fn lower_bound(&self, c: &Self::PartialCandidate) -> Self::Cost {
+        self.cost(c)
+    }
+}
+
+/// What a search did.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SearchStats {
+    pub visited: usize,
+    pub rejected: usize,
+    pub accepted: usize,
+    pub max_depth: usize,
+    pub elapsed: Duration,
+
+    /// `false` if the search stopped before exploring the whole tree.
+    pub complete: bool,
+}
+
+impl SearchStats {
+    fn visit(&mut self, depth: usize) {
+        self.visited += 1;
+        self.max_depth = self.max_depth.max(depth);
+    }
+
+    fn merge(&mut self, other: &SearchStats) {
+        self.visited += other.visited;
+        self.rejected += other.rejected;
+        self.accepted += other.accepted;
+        self.max_depth = self.max_depth.max(other.max_depth);
+    }
+}
+
+impl std::fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} nodes visited, {} rejected, {} accepted, max depth {}, {:?}{}",
+            self.visited,
+            self.rejected,
+            self.accepted,
+            self.max_depth,
+            self.elapsed,
+            if self.complete {
+                ""
+            } else {
+                " (stopped early)"
+            }
+        )
+    }
+}
+
+/// Driver for `BackTracking` problems.
+///
+/// Unlike `BackTracking::backtrack_recursive` it keeps the search path on the heap, so
+/// the depth of the search tree is not limited by the stack size.
+#[derive(Debug, Default, Clone)]
+pub struct Search {
+    max_solutions: Option<usize>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Search::default()
+    }
+
+    /// Stop as soon as `n` solutions have been found.
+    pub fn stop_after(mut self, n: usize) -> Self {
+        self.max_solutions = Some(n);
+        self
+    }
+
+    /// Stop when `flag` is set, e.g. from another thread.
+    pub fn cancel_on(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .map(|flag| flag.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
+    fn enough(&self, n_solutions: usize) -> bool {
+        self.max_solutions
+            .map(|max| n_solutions >= max)
+            .unwrap_or(false)
+    }
+
+    /// Depth-first search in the same order as `backtrack_recursive`.
+    pub fn run<B: BackTracking>(&self, problem: &mut B) -> SearchStats {
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+
+        let mut dfs = DepthFirst::new(problem.root());
+
+        stats.complete = loop {
+            if self.cancelled() || self.enough(stats.accepted) {
+                break false;
+            }
+
+            let (c, depth) = match dfs.next() {
+                Some(x) => x,
+                None => break true,
+            };
+
+            stats.visit(depth);
+
+            match problem.evaluate(&c) {
+                Verdict::Reject => {
+                    stats.rejected += 1;
+                    continue;
+                }
+                Verdict::Accept => {
+                    stats.accepted += 1;
+                    problem.output(&c);
+                }
+                Verdict::Partial => {}
+            }
+
+            dfs.push(problem.extend(&c));
+        };
+
+        stats.elapsed = start.elapsed();
+        stats
+    }
+
+    /// Depth-first search that skips every candidate whose lower bound is not better than
+    /// the best solution found so far.
+    ///
+    /// Each improvement is passed to `output`, so the last one is optimal; its cost is
+    /// returned. `stop_after` counts improvements.
+    pub fn branch_and_bound<B: Optimization>(
+        &self,
+        problem: &mut B,
+    ) -> (Option<B::Cost>, SearchStats) {
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let mut best = None;
+
+        let mut dfs = DepthFirst::new(problem.root());
+
+        stats.complete = loop {
+            if self.cancelled() || self.enough(stats.accepted) {
+                break false;
+            }
+
+            let (c, depth) = match dfs.next() {
+                Some(x) => x,
+                None => break true,
+            };
+
+            stats.visit(depth);
+
+            let hopeless = best
+                .map(|best| problem.lower_bound(&c) >= best)
+                .unwrap_or(false);
+            let verdict = if hopeless {
+                Verdict::Reject
+            } else {
+                problem.evaluate(&c)
+            };
+
+            if verdict == Verdict::Reject {
+                stats.rejected += 1;
+                continue;
+            }
+
+            if verdict == Verdict::Accept {
+                let cost = problem.cost(&c);
+                if best.map(|best| cost < best).unwrap_or(true) {
+                    best = Some(cost);
+                    stats.accepted += 1;
+                    problem.output(&c);
+                }
+            }
+
+            dfs.push(problem.extend(&c));
+        };
+
+        stats.elapsed = start.elapsed();
+        (best, stats)
+    }
+
+    /// Always expand the candidate with the lowest lower bound next.
+    ///
+    /// Solutions are passed to `output` in order of increasing cost; the cost of the first
+    /// (optimal) one is returned. Use `stop_after(1)` if only the optimum is of interest,
+    /// otherwise the whole tree is explored. All pending candidates are kept in memory.
+    pub fn best_first<B: Optimization>(&self, problem: &mut B) -> (Option<B::Cost>, SearchStats) {
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let mut best = None;
+
+        let mut seq = 0;
+        let mut queue = BinaryHeap::new();
+        let root = problem.root();
+        queue.push(Prioritized {
+            key: problem.lower_bound(&root),
+            solution: false,
+            seq,
+            depth: 0,
+            c: root,
+        });
+
+        stats.complete = loop {
+            if self.cancelled() || self.enough(stats.accepted) {
+                break false;
+            }
+
+            let entry = match queue.pop() {
+                Some(entry) => entry,
+                None => break true,
+            };
+
+            // nothing in the queue can lead to a cheaper solution than this one
+            if entry.solution {
+                best = best.or(Some(entry.key));
+                stats.accepted += 1;
+                problem.output(&entry.c);
+                continue;
+            }
+
+            stats.visit(entry.depth);
+
+            let verdict = problem.evaluate(&entry.c);
+            if verdict == Verdict::Reject {
+                stats.rejected += 1;
+                continue;
+            }
+
+            for child in problem.extend(&entry.c) {
+                seq += 1;
+                queue.push(Prioritized {
+                    key: problem.lower_bound(&child),
+                    solution: false,
+                    seq,
+                    depth: entry.depth + 1,
+                    c: child,
+                });
+            }
+
+            if verdict == Verdict::Accept {
+                seq += 1;
+                queue.push(Prioritized {
+                    key: problem.cost(&entry.c),
+                    solution: true,
+                    seq,
+                    ..entry
+                });
+            }
+        };
+
+        stats.elapsed = start.elapsed();
+        (best, stats)
+    }
+
+    /// Depth-first searches limited to depth 0, 1, 2, ... `max_depth`.
+    ///
+    /// Solutions are passed to `output` in order of increasing depth, while only the
+    /// current search path is kept in memory. Stops at the first depth limit that cuts
+    /// nothing off.
+    pub fn iterative_deepening<B: BackTracking>(
+        &self,
+        problem: &mut B,
+        max_depth: usize,
+    ) -> SearchStats {
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+
+        for limit in 0..=max_depth {
+            let mut cut_off = false;
+            let mut dfs = DepthFirst::new(problem.root());
+
+            while let Some((c, depth)) = dfs.next() {
+                if self.cancelled() || self.enough(stats.accepted) {
+                    stats.elapsed = start.elapsed();
+                    return stats;
+                }
+
+                stats.visit(depth);
+
+                let verdict = problem.evaluate(&c);
+                if verdict == Verdict::Reject {
+                    stats.rejected += 1;
+                    continue;
+                }
+
+                if depth < limit {
+                    dfs.push(problem.extend(&c));
+                    continue;
+                }
+
+                // shallower solutions have been reported in earlier iterations
+                if verdict == Verdict::Accept {
+                    stats.accepted += 1;
+                    problem.output(&c);
+                }
+
+                cut_off = cut_off || problem.extend(&c).next().is_some();
+            }
+
+            if !cut_off {
+                stats.complete = true;
+                break;
+            }
+        }
+
+        stats.elapsed = start.elapsed();
+        stats
+    }
+
+    /// Search with `n_threads` worker threads.
+    ///
+    /// Every worker explores its own part of the tree depth-first and steals the
+    /// shallowest pending candidate of another worker when it runs out of work. Solutions
+    /// are passed to `output` on the calling thread after all workers have finished, in no
+    /// particular order.
+    pub fn run_parallel<B>(&self, problem: &mut B, n_threads: usize) -> SearchStats
+    where
+        B: BackTracking + Sync,
+        B::PartialCandidate: Send,
+    {
+        let start = Instant::now();
+        let n_threads = n_threads.max(1);
+
+        let queues: Vec<_> = (0..n_threads)
+            .map(|_| Mutex::new(VecDeque::new()))
+            .collect();
+        queues[0].lock().unwrap().push_back((problem.root(), 0));
+
+        let shared = Shared {
+            queues,
+            pending: AtomicUsize::new(1),
+            stop: AtomicBool::new(false),
+            solutions: Mutex::new(vec![]),
+        };
+
+        let mut stats = SearchStats::default();
+        {
+            let problem = &*problem;
+            let shared = &shared;
+            let worker_stats: Vec<_> = std::thread::scope(|s| {
+                let workers: Vec<_> = (0..n_threads)
+                    .map(|id| s.spawn(move || self.worker(id, problem, shared)))
+                    .collect();
+                workers.into_iter().map(|w| w.join().unwrap()).collect()
+            });
+            for ws in &worker_stats {
+                stats.merge(ws);
+            }
+        }
+
+        let solutions = shared.solutions.into_inner().unwrap();
+        stats.accepted = solutions.len();
+        stats.complete = shared.pending.load(Ordering::SeqCst) == 0;
+        for c in &solutions {
+            problem.output(c);
+        }
+
+        stats.elapsed = start.elapsed();
+        stats
+    }
+
+    fn worker<B: BackTracking>(
+        &self,
+        id: usize,
+        problem: &B,
+        shared: &Shared<B::PartialCandidate>,
+    ) -> SearchStats {
+        let mut stats = SearchStats::default();
+
+        loop {
+            if self.cancelled() || shared.stop.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let (c, depth) = match shared.take(id) {
+                Some(item) => item,
+                None if shared.pending.load(Ordering::SeqCst) == 0 => break,
+                None => {
+                    std::thread::yield_now();
+                    continue;
+                }
+            };
+
+            stats.visit(depth);
+
+            let verdict = problem.evaluate(&c);
+            if verdict == Verdict::Reject {
+                stats.rejected += 1;
+            } else {
+                let accepted = verdict == Verdict::Accept;
+
+                let children: Vec<_> = problem.extend(&c).collect();
+                shared.pending.fetch_add(children.len(), Ordering::SeqCst);
+                shared.queues[id]
+                    .lock()
+                    .unwrap()
+                    .extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+
+                if accepted {
+                    let mut solutions = shared.solutions.lock().unwrap();
+                    if !self.enough(solutions.len()) {
+                        solutions.push(c);
+                    }
+                    if self.enough(solutions.len()) {
+                        shared.stop.store(true, Ordering::SeqCst);
+                    }
+                }
+            }
+
+            shared.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        stats
+    }
+}
+
+/// The explicit stack of a depth-first search.
+struct DepthFirst<C> {
+    next: Option<C>,
+    stack: Vec<Box<dyn Iterator<Item = C>>>,
+}
+
+impl<C> DepthFirst<C> {
+    fn new(root: C) -> Self {
+        DepthFirst {
+            next: Some(root),
+            stack: vec![],
+        }
+    }
+
+    /// The next candidate and its depth.
+    fn next(&mut self) -> Option<(C, usize)> {
+        if let Some(c) = self.next.take() {
+            return Some((c, self.stack.len()));
+        }
+        while let Some(children) = self.stack.last_mut() {
+            if let Some(c) = children.next() {
+                return Some((c, self.stack.len()));
+            }
+            self.stack.pop();
+        }
+        None
+    }
+
+    /// Continue with the children of the candidate returned last.
+    fn push(&mut self, children: Box<dyn Iterator<Item = C>>) {
+        self.stack.push(children);
+    }
+}
+
+/// Queue entry of the best-first search; the lowest key comes first, then solutions, then
+/// the candidate queued last.
+struct Prioritized<C, K> {
+    key: K,
+    solution: bool,
+    seq: usize,
+    depth: usize,
+    c: C,
+}
+
+impl<C, K: Ord> Ord for Prioritized<C, K> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .key
+            .cmp(&self.key)
+            .then(self.solution.cmp(&other.solution))
+            .then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl<C, K: Ord> PartialOrd for Prioritized<C, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C, K: Ord> PartialEq for Prioritized<C, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl<C, K: Ord> Eq for Prioritized<C, K> {}
+
+struct Shared<C> {
+    /// Each worker pushes and pops at the back; thieves take from the front.
+    queues: Vec<Mutex<VecDeque<(C, usize)>>>,
+
+    /// Candidates that are queued or being processed.
+    pending: AtomicUsize,
+
+    stop: AtomicBool,
+    solutions: Mutex<Vec<C>>,
+}
+
+impl<C> Shared<C> {
+    fn take(&self, id: usize) -> Option<(C, usize)> {
+        if let Some(item) = self.queues[id].lock().unwrap().pop_back() {
+            return Some(item);
+        }
+
+        let n = self.queues.len();
+        (1..n)
+            .map(|offset| (id + offset) % n)
+            .filter_map(|victim| self.queues[victim].lock().unwrap().pop_front())
+            .next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Place `n` queens on an `n`x`n` board.
+    struct Queens {
+        n: usize,
+        solutions: Vec<Vec<usize>>,
+    }
+
+    impl Queens {
+        fn new(n: usize) -> Self {
+            Queens {
+                n,
+                solutions: vec![],
+            }
+        }
+    }
+
+    impl BackTracking for Queens {
+        type PartialCandidate = Vec<usize>;
+
+        fn output(&mut self, c: &Vec<usize>) {
+            self.solutions.push(c.clone());
+        }
+
+        fn root(&self) -> Vec<usize> {
+            vec![]
+        }
+
+        fn reject(&self, c: &Vec<usize>) -> bool {
+            let (&last, others) = match c.split_last() {
+                Some(x) => x,
+                None => return false,
+            };
+            let row = others.len();
+            others
+                .iter()
+                .enumerate()
+                .any(|(r, &col)| col == last || row - r == col.max(last) - col.min(last))
+        }
+
+        fn accept(&self, c: &Vec<usize>) -> bool {
+            c.len() == self.n
+        }
+
+        fn extend(&self, c: &Vec<usize>) -> Box<dyn Iterator<Item = Vec<usize>>> {
+            if c.len() >= self.n {
+                return Box::new(std::iter::empty());
+            }
+            let c = c.clone();
+            Box::new((0..self.n).map(move |col| {
+                let mut next = c.clone();
+                next.push(col);
+                next
+            }))
+        }
+    }
+
+    /// A single path of length `n`.
+    struct Chain(usize);
+
+    impl BackTracking for Chain {
+        type PartialCandidate = usize;
+        fn output(&mut self, _: &usize) {}
+        fn root(&self) -> usize {
+            0
+        }
+        fn reject(&self, _: &usize) -> bool {
+            false
+        }
+        fn accept(&self, &c: &usize) -> bool {
+            c == self.0
+        }
+        fn extend(&self, &c: &usize) -> Box<dyn Iterator<Item = usize>> {
+            Box::new((c + 1..=self.0).take(1))
+        }
+    }
+
+    /// Pay `amount` with as few coins as possible.
+    struct Change {
+        amount: u32,
+        coins: Vec<u32>,
+        solutions: Vec<Vec<u32>>,
+    }
+
+    impl Change {
+        fn new(amount: u32, coins: &[u32]) -> Self {
+            Change {
+                amount,
+                coins: coins.to_vec(),
+                solutions: vec![],
+            }
+        }
+    }
+
+    impl BackTracking for Change {
+        type PartialCandidate = Vec<u32>;
+
+        fn output(&mut self, c: &Vec<u32>) {
+            self.solutions.push(c.clone());
+        }
+
+        fn root(&self) -> Vec<u32> {
+            vec![]
+        }
+
+        fn reject(&self, c: &Vec<u32>) -> bool {
+            c.iter().sum::<u32>() > self.amount
+        }
+
+        fn accept(&self, c: &Vec<u32>) -> bool {
+            c.iter().sum::<u32>() == self.amount
+        }
+
+        fn extend(&self, c: &Vec<u32>) -> Box<dyn Iterator<Item = Vec<u32>>> {
+            // coins in non-increasing order, so every multiset is generated once
+            let last = c.last().copied();
+            let c = c.clone();
+            let coins: Vec<_> = self
+                .coins
+                .iter()
+                .copied()
+                .filter(|&x| last.map(|last| x <= last).unwrap_or(true))
+                .collect();
+            Box::new(coins.into_iter().map(move |coin| {
+                let mut next = c.clone();
+                next.push(coin);
+                next
+            }))
+        }
+    }
+
+    impl Optimization for Change {
+        type Cost = usize;
+
+        fn cost(&self, c: &Vec<u32>) -> usize {
+            c.len()
+        }
+
+        fn lower_bound(&self, c: &Vec<u32>) -> usize {
+            let largest = *self.coins.iter().max().unwrap();
+            let missing = self.amount.saturating_sub(c.iter().sum());
+            match missing {
+                0 => c.len(),
+                _ => c.len() + 1 + ((missing - 1) / largest) as usize,
+            }
+        }
+    }
+
+    #[test]
+    fn iterative_matches_recursive() {
+        let mut recursive = Queens::new(6);
+        recursive.backtrack_recursive(&vec![]);
+
+        let mut iterative = Queens::new(6);
+        let stats = iterative.backtrack();
+
+        assert_eq!(iterative.solutions, recursive.solutions);
+        assert_eq!(stats.accepted, 4);
+        assert_eq!(stats.max_depth, 6);
+        assert!(stats.complete);
+    }
+
+    #[test]
+    fn stop_after_first_solutions() {
+        let mut all = Queens::new(8);
+        all.backtrack();
+        assert_eq!(all.solutions.len(), 92);
+
+        let mut first = Queens::new(8);
+        let stats = Search::new().stop_after(1).run(&mut first);
+        assert_eq!(first.solutions, all.solutions[..1].to_vec());
+        assert!(!stats.complete);
+
+        let mut some = Queens::new(8);
+        Search::new().stop_after(10).run(&mut some);
+        assert_eq!(some.solutions, all.solutions[..10].to_vec());
+    }
+
+    #[test]
+    fn cancel() {
+        let flag = Arc::new(AtomicBool::new(true));
+        let mut queens = Queens::new(8);
+        let stats = Search::new().cancel_on(flag).run(&mut queens);
+        assert_eq!(stats.visited, 0);
+        assert!(!stats.complete);
+    }
+
+    #[test]
+    fn deep_search() {
+        let stats = Chain(1_000_000).backtrack();
+        assert_eq!(stats.accepted, 1);
+        assert_eq!(stats.max_depth, 1_000_000);
+    }
+
+    #[test]
+    fn parallel_finds_all_solutions() {
+        let mut sequential = Queens::new(8);
+        let expected = sequential.backtrack();
+
+        for &n_threads in &[1, 2, 4, 8] {
+            let mut parallel = Queens::new(8);
+            let stats = Search::new().run_parallel(&mut parallel, n_threads);
+
+            let mut solutions = parallel.solutions.clone();
+            solutions.sort();
+            assert_eq!(solutions, sequential.solutions);
+            assert_eq!(stats.visited, expected.visited);
+            assert_eq!(stats.rejected, expected.rejected);
+            assert_eq!(stats.max_depth, expected.max_depth);
+            assert!(stats.complete);
+        }
+    }
+
+    #[test]
+    fn parallel_stop_after() {
+        let mut queens = Queens::new(10);
+        let stats = Search::new().stop_after(3).run_parallel(&mut queens, 4);
+        assert_eq!(queens.solutions.len(), 3);
+        assert_eq!(stats.accepted, 3);
+        for s in &queens.solutions {
+            assert_eq!(s.len(), 10);
+            assert!((1..=10).all(|k| !queens.reject(&s[..k].to_vec())));
+        }
+    }
+
+    #[test]
+    fn branch_and_bound() {
+        let mut change = Change::new(63, &[25, 21, 10, 5, 1]);
+        let (best, stats) = Search::new().branch_and_bound(&mut change);
+        assert_eq!(best, Some(3));
+        assert_eq!(change.solutions.last(), Some(&vec![21, 21, 21]));
+        assert!(change.solutions.windows(2).all(|w| w[1].len() < w[0].len()));
+        assert!(stats.complete);
+
+        let exhaustive = Change::new(63, &[25, 21, 10, 5, 1]).backtrack();
+        assert!(stats.visited < exhaustive.visited);
+    }
+
+    #[test]
+    fn best_first() {
+        let mut change = Change::new(63, &[25, 21, 10, 5, 1]);
+        let (best, stats) = Search::new().stop_after(1).best_first(&mut change);
+        assert_eq!(best, Some(3));
+        assert_eq!(change.solutions, vec![vec![21, 21, 21]]);
+        assert!(!stats.complete);
+
+        let mut change = Change::new(15, &[10, 5, 1]);
+        let (best, stats) = Search::new().best_first(&mut change);
+        assert_eq!(best, Some(2));
+        assert_eq!(stats.accepted, 6);
+        assert!(change
+            .solutions
+            .windows(2)
+            .all(|w| w[0].len() <= w[1].len()));
+        assert!(stats.complete);
+    }
+
+    #[test]
+    fn iterative_deepening() {
+        let mut change = Change::new(63, &[25, 21, 10, 5, 1]);
+        let stats = Search::new()
+            .stop_after(1)
+            .iterative_deepening(&mut change, 10);
+        assert_eq!(change.solutions, vec![vec![21, 21, 21]]);
+        assert_eq!(stats.max_depth, 3);
+
+        let mut queens = Queens::new(5);
+        let stats = Search::new().iterative_deepening(&mut queens, 10);
+        assert_eq!(queens.solutions.len(), 10);
+        assert_eq!(stats.accepted, 10);
+        assert!(stats.complete);
+
+        let mut queens = Queens::new(5);
+        let stats = Search::new().iterative_deepening(&mut queens, 3);
+        assert!(queens.solutions.is_empty());
+        assert!(!stats.complete);
+    }
+
+    /// Sets of distinct items, built in any order.
+    struct Subsets {
+        n: u8,
+        target: u32,
+        evaluated: AtomicUsize,
+        solutions: Vec<Vec<u8>>,
+    }
+
+    impl BackTracking for Subsets {
+        type PartialCandidate = Vec<u8>;
+
+        fn output(&mut self, c: &Vec<u8>) {
+            self.solutions.push(c.clone());
+        }
+
+        fn root(&self) -> Vec<u8> {
+            vec![]
+        }
+
+        fn reject(&self, c: &Vec<u8>) -> bool {
+            self.evaluate(c) == Verdict::Reject
+        }
+
+        fn accept(&self, c: &Vec<u8>) -> bool {
+            self.evaluate(c) == Verdict::Accept
+        }
+
+        fn extend(&self, c: &Vec<u8>) -> Box<dyn Iterator<Item = Vec<u8>>> {
+            let c = c.clone();
+            let items: Vec<_> = (0..self.n).filter(|x| !c.contains(x)).collect();
+            Box::new(items.into_iter().map(move |x| {
+                let mut next = c.clone();
+                next.push(x);
+                next
+            }))
+        }
+
+        fn evaluate(&self, c: &Vec<u8>) -> Verdict {
+            self.evaluated.fetch_add(1, Ordering::SeqCst);
+            let sum: u32 = c.iter().map(|&x| x as u32).sum();
+            match sum {
+                _ if sum > self.target => Verdict::Reject,
+                _ if sum == self.target => Verdict::Accept,
+                _ => Verdict::Partial,
+            }
+        }
+    }
+
+    impl Transposition for Subsets {
+        type Key = u32;
+
+        fn key(&self, c: &Vec<u8>) -> u32 {
+            c.iter().map(|&x| 1 << x).sum()
+        }
+    }
+
+    #[test]
+    fn memoized_evaluates_each_state_once() {
+        let subsets = |n| Subsets {
+            n,
+            target: 1000,
+            evaluated: AtomicUsize::new(0),
+            solutions: vec![],
+        };
+
+        let mut plain = subsets(5);
+        let plain_stats = plain.backtrack();
+        assert_eq!(plain_stats.visited, 326);
+        assert_eq!(plain.evaluated.load(Ordering::SeqCst), 326);
+
+        let mut memo = Memoized::new(subsets(5));
+        let stats = memo.backtrack();
+        assert_eq!(stats.visited, 32 + memo.duplicates());
+        assert_eq!(memo.inner().evaluated.load(Ordering::SeqCst), 32);
+        assert_eq!(memo.duplicates(), stats.rejected);
+
+        // answered from the cache, also for a duplicate of an accepted candidate
+        let mut memo = Memoized::new(Subsets {
+            target: 3,
+            ..subsets(3)
+        });
+        memo.backtrack();
+        let evaluated = memo.inner().evaluated.load(Ordering::SeqCst);
+        assert!(memo.accept(&vec![2, 1]));
+        assert!(memo.accept(&vec![1, 2]));
+        assert!(!memo.accept(&vec![1]));
+        assert_eq!(memo.inner().evaluated.load(Ordering::SeqCst), evaluated);
+    }
+
+    #[test]
+    fn memoized_finds_each_solution_once() {
+        let mut memo = Memoized::new(Subsets {
+            n: 8,
+            target: 10,
+            evaluated: AtomicUsize::new(0),
+            solutions: vec![],
+        });
+        memo.backtrack();
+
+        let mut found: Vec<_> = memo
+            .into_inner()
+            .solutions
+            .into_iter()
+            .map(|mut s| {
+                s.sort();
+                s
+            })
+            .collect();
+        found.sort();
+        let n_found = found.len();
+        found.dedup();
+        assert_eq!(found.len(), n_found);
+
+        let expected = (0u32..256)
+            .filter(|set| (0..8).filter(|i| set & (1 << i) != 0).sum::<u32>() == 10)
+            .count();
+        assert_eq!(n_found, expected);
+    }
+
+    #[test]
+    fn memoized_parallel_evaluates_each_state_once() {
+        // every subset is reached in many orders, by different workers at the same time
+        let subsets = || Subsets {
+            n: 8,
+            target: 10,
+            evaluated: AtomicUsize::new(0),
+            solutions: vec![],
+        };
+        let mut serial = Memoized::new(subsets());
+        serial.backtrack();
+        let states = serial.inner().evaluated.load(Ordering::SeqCst);
+
+        let mut memo = Memoized::new(subsets());
+        let stats = Search::new().run_parallel(&mut memo, 8);
+        assert_eq!(memo.inner().evaluated.load(Ordering::SeqCst), states);
+        assert_eq!(stats.visited, states + memo.duplicates());
+
+        let mut found: Vec<_> = memo
+            .into_inner()
+            .solutions
+            .into_iter()
+            .map(|s| s.iter().map(|&x| 1 << x).sum::<u32>())
+            .collect();
+        found.sort();
+        let n_found = found.len();
+        found.dedup();
+        assert_eq!(found.len(), n_found);
+        let expected = (0u32..256)
+            .filter(|set| (0..8).filter(|i| set & (1 << i) != 0).sum::<u32>() == 10)
+            .count();
+        assert_eq!(n_found, expected);
+    }
+
+    /// Paths through a small weighted graph; the node is the key.
+    struct Paths {
+        edges: Vec<(usize, usize, u32)>,
+        target: usize,
+        costs: Vec<u32>,
+    }
+
+    impl BackTracking for Paths {
+        type PartialCandidate = (usize, u32);
+
+        fn output(&mut self, c: &(usize, u32)) {
+            self.costs.push(c.1);
+        }
+
+        fn root(&self) -> (usize, u32) {
+            (0, 0)
+        }
+
+        fn reject(&self, _: &(usize, u32)) -> bool {
+            false
+        }
+
+        fn accept(&self, c: &(usize, u32)) -> bool {
+            c.0 == self.target
+        }
+
+        fn extend(&self, &(node, cost): &(usize, u32)) -> Box<dyn Iterator<Item = (usize, u32)>> {
+            let next: Vec<_> = self
+                .edges
+                .iter()
+                .filter(|e| e.0 == node)
+                .map(|e| (e.1, cost + e.2))
+                .collect();
+            Box::new(next.into_iter())
+        }
+    }
+
+    impl Optimization for Paths {
+        type Cost = u32;
+
+        fn cost(&self, c: &(usize, u32)) -> u32 {
+            c.1
+        }
+    }
+
+    impl Transposition for Paths {
+        type Key = usize;
+
+        fn key(&self, c: &(usize, u32)) -> usize {
+            c.0
+        }
+    }
+
+    #[test]
+    fn cheapest_revisits_keys_reached_cheaper() {
+        // depth-first search takes the expensive edge to 2 first
+        let paths = || Paths {
+            edges: vec![(0, 2, 5), (0, 1, 1), (1, 2, 1), (2, 3, 1)],
+            target: 3,
+            costs: vec![],
+        };
+
+        let mut problem = Cheapest::new(paths());
+        problem.backtrack();
+        assert_eq!(problem.inner().costs.iter().min(), Some(&3));
+        assert_eq!(problem.duplicates(), 0);
+
+        let mut problem = Cheapest::new(paths());
+        let (best, _) = Search::new().branch_and_bound(&mut problem);
+        assert_eq!(best, Some(3));
+
+        let mut problem = Cheapest::new(paths());
+        Search::new().run_parallel(&mut problem, 4);
+        assert_eq!(problem.inner().costs.iter().min(), Some(&3));
+
+        // best-first reaches every node the cheapest way first
+        let mut problem = Cheapest::new(paths());
+        let (best, _) = Search::new().best_first(&mut problem);
+        assert_eq!(best, Some(3));
+        assert_eq!(problem.duplicates(), 1);
+    }
+}
@@ -0,0 +1,187 @@
+//! Find cycles in sequences of states `x, f(x), f(f(x)), ...`.
+//!
+//! `floyd` and `brent` only keep a few states around and need nothing but equality; `hashed`
+//! remembers every state and finds the cycle with the fewest steps. None of them terminate if
+//! the sequence never repeats.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The sequence enters a loop of `period` states after the first `tail` states.
+///
+/// The state after `tail + period` steps is the first one that was seen before.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The fewest steps that lead to the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.period
+        }
+    }
+
+    /// The state after `n` steps, simulating fewer than `tail + period` of them.
+    pub fn extrapolate<T: Clone>(&self, start: &T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+        let mut state = start.clone();
+        for _ in 0..self.reduce(n) {
+            state = step(&state);
+        }
+        state
+    }
+}
+
+/// Floyd's tortoise and hare.
+pub fn floyd<T: Clone + Eq>(start: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut tortoise = step(start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let halfway = step(&hare);
+        hare = step(&halfway);
+    }
+
+    // the distance between them is a multiple of the period
+    let mut tail = 0;
+    tortoise = start.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+/// Brent's algorithm; usually needs fewer steps than `floyd`.
+pub fn brent<T: Clone + Eq>(start: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tail = 0;
+    tortoise = start.clone();
+    hare = start.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        tail += 1;
+    }
+
+    Cycle { tail, period }
+}
+
+/// Remember every state; each state is computed only once.
+pub fn hashed<T: Clone + Hash + Eq>(start: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = start.clone();
+    let mut n = 0;
+    loop {
+        let next = step(&state);
+        if let Some(tail) = seen.insert(state, n) {
+            return Cycle {
+                tail,
+                period: n - tail,
+            };
+        }
+        state = next;
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    fn brute_force(start: u32) -> Cycle {
+        let mut states = vec![start];
+        loop {
+            let next = f(states.last().unwrap());
+            if let Some(tail) = states.iter().position(|&s| s == next) {
+                return Cycle {
+                    tail,
+                    period: states.len() - tail,
+                };
+            }
+            states.push(next);
+        }
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        for start in 0..255 {
+            let expected = brute_force(start);
+            assert_eq!(floyd(&start, f), expected, "start {}", start);
+            assert_eq!(brent(&start, f), expected, "start {}", start);
+            assert_eq!(hashed(&start, f), expected, "start {}", start);
+        }
+    }
+
+    #[test]
+    fn pure_cycle() {
+        let rotate = |v: &Vec<u8>| {
+            let mut v = v.clone();
+            v.rotate_left(1);
+            v
+        };
+        let start = vec![1, 2, 3, 4, 5, 6];
+        let expected = Cycle { tail: 0, period: 6 };
+        assert_eq!(floyd(&start, rotate), expected);
+        assert_eq!(brent(&start, rotate), expected);
+        assert_eq!(hashed(&start, rotate), expected);
+        assert_eq!(brent(&0, |x| *x), Cycle { tail: 0, period: 1 });
+    }
+
+    #[test]
+    fn extrapolate() {
+        let cycle = brent(&3, f);
+        assert!(cycle.tail > 0);
+        let mut state = 3;
+        for n in 0..100 {
+            assert_eq!(cycle.extrapolate(&3, f, n), state, "{} steps", n);
+            state = f(&state);
+        }
+        assert_eq!(cycle.reduce(cycle.tail + cycle.period), cycle.tail);
+
+        let steps = std::cell::Cell::new(0);
+        let counting = |x: &u32| {
+            steps.set(steps.get() + 1);
+            f(x)
+        };
+        let far = cycle.extrapolate(&3, counting, 1_000_000_000_000);
+        assert_eq!(
+            far,
+            cycle.extrapolate(&3, f, cycle.reduce(1_000_000_000_000))
+        );
+        assert!(steps.get() < cycle.tail + cycle.period);
+    }
+}
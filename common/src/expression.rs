@@ -26,10 +26,6 @@ impl Expression {
 }
 
 impl Computable for Expression {
-    fn invalid() -> Self {
-        Expression::Invalid
-    }
-
     fn as_i64(&self) -> i64 {
         self.eval(&HashMap::new())
     }
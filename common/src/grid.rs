@@ -0,0 +1,660 @@
+//! Positions, directions and grids for map-based puzzles.
+//!
+//! `y` grows downwards (south), as in the puzzle inputs.
+
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Index, IndexMut, Neg, Sub};
+
+#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Pos {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Pos {
+    pub fn new(x: i64, y: i64) -> Self {
+        Pos { x, y }
+    }
+
+    /// The four orthogonal neighbors.
+    pub fn neighbors(self) -> impl Iterator<Item = Pos> {
+        Direction::ALL.iter().map(move |&d| self + d)
+    }
+
+    /// The four orthogonal and four diagonal neighbors.
+    pub fn neighbors8(self) -> impl Iterator<Item = Pos> {
+        Direction8::ALL.iter().map(move |&d| self + d)
+    }
+
+    pub fn manhattan(self, other: Pos) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+    fn add(self, other: Pos) -> Pos {
+        Pos::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+    fn sub(self, other: Pos) -> Pos {
+        Pos::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add<Direction> for Pos {
+    type Output = Pos;
+    fn add(self, dir: Direction) -> Pos {
+        self + dir.delta()
+    }
+}
+
+impl AddAssign<Direction> for Pos {
+    fn add_assign(&mut self, dir: Direction) {
+        *self = *self + dir;
+    }
+}
+
+impl Add<Direction8> for Pos {
+    type Output = Pos;
+    fn add(self, dir: Direction8) -> Pos {
+        self + dir.delta()
+    }
+}
+
+impl AddAssign<Direction8> for Pos {
+    fn add_assign(&mut self, dir: Direction8) {
+        *self = *self + dir;
+    }
+}
+
+/// The four orthogonal directions.
+///
+/// The discriminants are the movement commands of the repair droid (day 15).
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Direction {
+    North = 1,
+    South = 2,
+    West = 3,
+    East = 4,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+    ];
+
+    pub fn delta(self) -> Pos {
+        match self {
+            Direction::North => Pos::new(0, -1),
+            Direction::South => Pos::new(0, 1),
+            Direction::West => Pos::new(-1, 0),
+            Direction::East => Pos::new(1, 0),
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        -self.turn_left()
+    }
+}
+
+impl Neg for Direction {
+    type Output = Self;
+    fn neg(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
+/// The four orthogonal and four diagonal directions, clockwise from north.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    pub fn delta(self) -> Pos {
+        match self {
+            Direction8::North => Pos::new(0, -1),
+            Direction8::NorthEast => Pos::new(1, -1),
+            Direction8::East => Pos::new(1, 0),
+            Direction8::SouthEast => Pos::new(1, 1),
+            Direction8::South => Pos::new(0, 1),
+            Direction8::SouthWest => Pos::new(-1, 1),
+            Direction8::West => Pos::new(-1, 0),
+            Direction8::NorthWest => Pos::new(-1, -1),
+        }
+    }
+
+    /// Turn 45 degrees counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        Direction8::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Turn 45 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        Direction8::ALL[(self as usize + 1) % 8]
+    }
+}
+
+impl Neg for Direction8 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Direction8::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Self {
+        match dir {
+            Direction::North => Direction8::North,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+            Direction::East => Direction8::East,
+        }
+    }
+}
+
+/// The smallest rectangle containing a set of positions; both corners are inclusive.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct BoundingBox {
+    pub min: Pos,
+    pub max: Pos,
+}
+
+impl BoundingBox {
+    /// Returns `None` if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Pos>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bb = BoundingBox {
+            min: first,
+            max: first,
+        };
+        for p in points {
+            bb.extend(p);
+        }
+        Some(bb)
+    }
+
+    pub fn extend(&mut self, p: Pos) {
+        self.min.x = self.min.x.min(p.x);
+        self.min.y = self.min.y.min(p.y);
+        self.max.x = self.max.x.max(p.x);
+        self.max.y = self.max.y.max(p.y);
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn contains(&self, p: Pos) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// All positions inside, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Pos::new(x, y)))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GridError {
+    /// Line `line` (counting from 0) does not have the same length as the first line.
+    RaggedLine {
+        line: usize,
+        len: usize,
+        expected: usize,
+    },
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GridError::RaggedLine {
+                line,
+                len,
+                expected,
+            } => write!(
+                f,
+                "line {} has {} characters, expected {}",
+                line, len, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// A dense, rectangular grid with its top left cell at `(0, 0)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Row by row; panics if the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len() % width.max(1), 0, "incomplete last row");
+        Grid {
+            width,
+            height: cells.len() / width.max(1),
+            cells,
+        }
+    }
+}
+
+impl Grid<char> {
+    /// One cell per character; all lines must have the same length.
+    pub fn parse_chars(text: &str) -> Result<Self, GridError> {
+        Self::parse(text, |ch| ch)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Convert each character with `f`; all lines must have the same length.
+    ///
+    /// Trailing empty lines are ignored.
+    pub fn parse(text: &str, mut f: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        let lines: Vec<_> = text.trim_end_matches('\n').lines().collect();
+        let width = lines.first().map(|l| l.chars().count()).unwrap_or(0);
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            if len != width {
+                return Err(GridError::RaggedLine {
+                    line: i,
+                    len,
+                    expected: width,
+                });
+            }
+            cells.extend(line.chars().map(&mut f));
+        }
+
+        Ok(Grid {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Pos) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    fn offset(&self, p: Pos) -> Option<usize> {
+        if self.contains(p) {
+            Some(p.y as usize * self.width + p.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, p: Pos) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Pos) -> Option<&mut T> {
+        self.offset(p).map(move |i| &mut self.cells[i])
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        if self.cells.is_empty() {
+            None
+        } else {
+            Some(BoundingBox {
+                min: Pos::new(0, 0),
+                max: Pos::new(self.width as i64 - 1, self.height as i64 - 1),
+            })
+        }
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width as i64;
+        (0..self.cells.len() as i64).map(move |i| Pos::new(i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The orthogonal neighbors of `p` that are inside the grid.
+    pub fn neighbors(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        p.neighbors().filter(move |&n| self.contains(n))
+    }
+
+    /// The orthogonal and diagonal neighbors of `p` that are inside the grid.
+    pub fn neighbors8(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        p.neighbors8().filter(move |&n| self.contains(n))
+    }
+
+    /// Position of the first cell (row by row) that satisfies `pred`.
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, t)| pred(t)).map(|(p, _)| p)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+    fn index(&self, p: Pos) -> &T {
+        match self.get(p) {
+            Some(t) => t,
+            None => panic!(
+                "{:?} out of bounds of {}x{} grid",
+                p, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, p: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(p) {
+            Some(t) => t,
+            None => panic!("{:?} out of bounds of {}x{} grid", p, width, height),
+        }
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// An unbounded grid that stores only the cells that have been set.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, p: Pos) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Pos) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn insert(&mut self, p: Pos, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Pos) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn contains(&self, p: Pos) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// The set cells in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.cells.iter().map(|(&p, t)| (p, t))
+    }
+
+    /// The orthogonal neighbors of `p` that are set.
+    pub fn neighbors(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        p.neighbors().filter(move |&n| self.contains(n))
+    }
+
+    /// The orthogonal and diagonal neighbors of `p` that are set.
+    pub fn neighbors8(&self, p: Pos) -> impl Iterator<Item = Pos> + '_ {
+        p.neighbors8().filter(move |&n| self.contains(n))
+    }
+
+    /// The bounding box of all set cells, or `None` if there are none.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.cells.keys().copied())
+    }
+
+    /// Copy into a dense grid that covers the bounding box; the cell at `bb.min` ends up
+    /// at `(0, 0)`. Unset cells are `fill`.
+    pub fn to_dense(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let bb = match self.bounding_box() {
+            Some(bb) => bb,
+            None => return Grid::new(0, 0, fill),
+        };
+        let mut grid = Grid::new(bb.width(), bb.height(), fill);
+        for (p, t) in self.iter() {
+            grid[p - bb.min] = t.clone();
+        }
+        grid
+    }
+}
+
+impl<T> Index<Pos> for SparseGrid<T> {
+    type Output = T;
+    fn index(&self, p: Pos) -> &T {
+        &self.cells[&p]
+    }
+}
+
+impl<T> std::iter::FromIterator<(Pos, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Pos, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<(Pos, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Pos, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter)
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        let width = grid.width as i64;
+        grid.cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| (Pos::new(i as i64 % width, i as i64 / width), t))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "#####
+#..@#
+#.#.#
+#####
+";
+
+    #[test]
+    fn directions() {
+        for &d in &Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_left().turn_left(), -d);
+            assert_eq!(Pos::default() + d + -d, Pos::default());
+            assert_eq!(
+                Direction8::from(d).turn_left().turn_left(),
+                d.turn_left().into()
+            );
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::East as i64, 4);
+
+        for &d in &Direction8::ALL {
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.delta() + (-d).delta(), Pos::default());
+        }
+        assert_eq!(Direction8::NorthWest.turn_right(), Direction8::North);
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = Pos::new(3, -2);
+        assert_eq!(p.neighbors().count(), 4);
+        assert!(p.neighbors().all(|n| n.manhattan(p) == 1));
+
+        let mut n8: Vec<_> = p.neighbors8().collect();
+        n8.sort();
+        n8.dedup();
+        assert_eq!(n8.len(), 8);
+        assert!(!n8.contains(&p));
+    }
+
+    #[test]
+    fn parse_grid() {
+        let grid = Grid::parse_chars(MAZE).unwrap();
+        assert_eq!((grid.width(), grid.height()), (5, 4));
+        assert_eq!(grid.find(|&ch| ch == '@'), Some(Pos::new(3, 1)));
+        assert_eq!(grid[Pos::new(1, 2)], '.');
+        assert_eq!(grid.get(Pos::new(5, 0)), None);
+        assert_eq!(grid.get(Pos::new(0, -1)), None);
+        assert_eq!(grid.to_string(), MAZE);
+
+        let open: Vec<_> = grid
+            .neighbors(Pos::new(3, 1))
+            .filter(|&p| grid[p] != '#')
+            .collect();
+        assert_eq!(open, vec![Pos::new(3, 2), Pos::new(2, 1)]);
+        assert_eq!(grid.neighbors(Pos::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors8(Pos::new(0, 0)).count(), 3);
+    }
+
+    #[test]
+    fn parse_ragged() {
+        assert_eq!(
+            Grid::parse_chars("###\n##\n###"),
+            Err(GridError::RaggedLine {
+                line: 1,
+                len: 2,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let grid = Grid::new(3, 3, 0);
+        let _ = grid[Pos::new(3, 0)];
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut sparse: SparseGrid<_> = Grid::parse(MAZE, |ch| ch == '#').unwrap().into();
+        assert_eq!(sparse.len(), 20);
+        sparse.insert(Pos::new(-2, 5), true);
+
+        let bb = sparse.bounding_box().unwrap();
+        assert_eq!(bb.min, Pos::new(-2, 0));
+        assert_eq!(bb.max, Pos::new(4, 5));
+        assert_eq!((bb.width(), bb.height()), (7, 6));
+        assert_eq!(bb.positions().count(), 42);
+        assert!(bb.positions().all(|p| bb.contains(p)));
+
+        let dense = sparse.to_dense(false);
+        assert!(dense[Pos::new(0, 5)]);
+        assert!(dense[Pos::new(2, 0)]);
+        assert!(!dense[Pos::new(0, 0)]);
+
+        assert_eq!(SparseGrid::<u8>::new().bounding_box(), None);
+    }
+}
@@ -0,0 +1,207 @@
+//! Load puzzle inputs.
+//!
+//! By default the input of day `n` is read from `data/dayNN-input.txt` in the workspace root, or
+//! from the directory in `$AOC_DATA`. The first command line argument overrides this: a path is
+//! read instead, and `-` reads stdin.
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub enum InputError {
+    /// Reading from `path` failed; `None` is stdin.
+    Io {
+        path: Option<PathBuf>,
+        error: io::Error,
+    },
+
+    /// `token` on line `line` (counting from 1) could not be parsed.
+    Parse {
+        line: usize,
+        token: String,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InputError::Io { path: None, error } => write!(f, "reading stdin: {}", error),
+            InputError::Io {
+                path: Some(path),
+                error,
+            } if error.kind() == io::ErrorKind::NotFound => write!(
+                f,
+                "{} not found; set $AOC_DATA or pass another input path, or - to read stdin",
+                path.display()
+            ),
+            InputError::Io {
+                path: Some(path),
+                error,
+            } => write!(f, "reading {}: {}", path.display(), error),
+            InputError::Parse { line, token, error } => {
+                write!(f, "line {}: cannot parse {:?}: {}", line, token, error)
+            }
+        }
+    }
+}
+
+// `main` returning an error prints it with `Debug`
+impl std::fmt::Debug for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Where to read an input from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Source {
+    /// The day's file in `data_dir()`, or the embedded input if there is one.
+    Default,
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interpret a command line argument.
+    pub fn from_arg(arg: Option<&str>) -> Self {
+        match arg {
+            None => Source::Default,
+            Some("-") => Source::Stdin,
+            Some(path) => Source::File(path.into()),
+        }
+    }
+
+    /// Interpret the first command line argument.
+    pub fn from_args() -> Self {
+        Self::from_arg(std::env::args().nth(1).as_deref())
+    }
+
+    pub fn read(&self, day: u32) -> Result<String, InputError> {
+        match self {
+            Source::Default => read_file(&default_path(day)),
+            Source::File(path) => read_file(path),
+            Source::Stdin => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|error| InputError::Io { path: None, error })?;
+                Ok(text)
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    std::fs::read_to_string(path).map_err(|error| InputError::Io {
+        path: Some(path.to_owned()),
+        error,
+    })
+}
+
+pub fn data_dir() -> PathBuf {
+    match std::env::var_os("AOC_DATA") {
+        Some(dir) => dir.into(),
+        None => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("data"),
+    }
+}
+
+pub fn default_path(day: u32) -> PathBuf {
+    data_dir().join(format!("day{:02}-input.txt", day))
+}
+
+/// Parse a comma separated intcode program; it may span several lines.
+pub fn intcode(text: &str) -> Result<Vec<i64>, InputError> {
+    let mut program = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim().trim_end_matches(',');
+        if line.is_empty() {
+            continue;
+        }
+        for token in line.split(',') {
+            program.push(parse_token(i, token.trim())?);
+        }
+    }
+    Ok(program)
+}
+
+/// Parse every non-empty line.
+pub fn lines<T>(text: &str) -> Result<Vec<T>, InputError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_token(i, line.trim()))
+        .collect()
+}
+
+fn parse_token<T>(line: usize, token: &str) -> Result<T, InputError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    token.parse().map_err(|e: T::Err| InputError::Parse {
+        line: line + 1,
+        token: token.to_string(),
+        error: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources() {
+        assert_eq!(Source::from_arg(None), Source::Default);
+        assert_eq!(Source::from_arg(Some("-")), Source::Stdin);
+        assert_eq!(
+            Source::from_arg(Some("in.txt")),
+            Source::File("in.txt".into())
+        );
+        assert!(default_path(7).ends_with("day07-input.txt"));
+    }
+
+    #[test]
+    fn read_files() {
+        let path = std::env::temp_dir().join(format!("aoc-input-{}.txt", std::process::id()));
+        std::fs::write(&path, "1,2,\n3\n").unwrap();
+        let source = Source::File(path.clone());
+        assert_eq!(intcode(&source.read(1).unwrap()).unwrap(), vec![1, 2, 3]);
+        std::fs::remove_file(&path).unwrap();
+
+        let err = source.read(1).unwrap_err();
+        assert!(err.to_string().contains("not found"), "{}", err);
+    }
+
+    #[test]
+    fn parse_intcode() {
+        assert_eq!(intcode("1,-2, 3\n").unwrap(), vec![1, -2, 3]);
+        assert_eq!(intcode("").unwrap(), vec![]);
+        assert_eq!(
+            intcode("1,2\n3,x").unwrap_err().to_string(),
+            "line 2: cannot parse \"x\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(
+            lines::<u32>("12\n14\r\n\n1969\n").unwrap(),
+            vec![12, 14, 1969]
+        );
+        assert_eq!(lines::<String>("a)b\nb)c").unwrap(), vec!["a)b", "b)c"]);
+        match lines::<u32>("1\n-2\n") {
+            Err(InputError::Parse { line: 2, token, .. }) => assert_eq!(token, "-2"),
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,1012 @@
+//! Abstract interpretation of intcode programs.
+//!
+//! The analysis runs a program on ranges of values instead of values. Where a branch depends
+//! on a value that is not known exactly, both sides are explored, and the states of all paths
+//! that reach an instruction are joined. Bounds that keep growing in loops are dropped
+//! (widening), so the analysis terminates. When a branch tests the result of a comparison,
+//! each side continues with the compared cells restricted to the values that take it. The
+//! result covers every run whose inputs lie in the given ranges, except where it lists the
+//! instruction as incomplete: runs that fault, or that the analysis loses track of, are not
+//! followed past it.
+//!
+//! Internally, values are small sets where possible, so that return addresses on the stack
+//! stay exact and calls can be followed back to their call sites. States are only joined if
+//! they have the same relative base and the same return addresses for the innermost calls,
+//! which are taken to be where the relative base pointed when it was increased. This keeps
+//! calls of a function from different sites and stack depths apart.
+
+use crate::intcode2::{Isa, Op, Operand, MEMORY_SIZE};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Sets of more values than this are approximated by their interval.
+const MAX_SET: usize = 8;
+
+/// Address ranges with more cells than this are not tracked cell by cell.
+const MAX_CELLS: i64 = 64;
+
+/// States are joined into an instruction's state this often before widening.
+const WIDEN_DELAY: u32 = 4;
+
+/// Runs that reach an instruction with more relative bases than this are not followed, as
+/// they are most likely stuck in a recursion that the analysis cannot bound.
+const MAX_DEPTHS: usize = 8;
+
+/// States are kept apart by this many of the innermost return addresses.
+const CALL_STRING: usize = 4;
+
+/// A closed range of integers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Interval {
+    pub lo: i64,
+    pub hi: i64,
+}
+
+impl Interval {
+    pub const TOP: Interval = Interval {
+        lo: i64::MIN,
+        hi: i64::MAX,
+    };
+
+    pub fn new(lo: i64, hi: i64) -> Self {
+        assert!(lo <= hi, "empty interval [{}, {}]", lo, hi);
+        Interval { lo, hi }
+    }
+
+    pub fn constant(x: i64) -> Self {
+        Interval { lo: x, hi: x }
+    }
+
+    pub fn as_constant(&self) -> Option<i64> {
+        if self.lo == self.hi {
+            Some(self.lo)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    pub fn join(self, other: Self) -> Self {
+        Interval {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// `self.join(newer)`, with the bounds that grew dropped.
+    fn widen(self, newer: Self) -> Self {
+        Interval {
+            lo: if newer.lo < self.lo {
+                i64::MIN
+            } else {
+                self.lo
+            },
+            hi: if newer.hi > self.hi {
+                i64::MAX
+            } else {
+                self.hi
+            },
+        }
+    }
+
+    /// The part that is a valid memory address.
+    fn addresses(self) -> Option<Self> {
+        let lo = self.lo.max(0);
+        let hi = self.hi.min(MEMORY_SIZE as i64 - 1);
+        if lo <= hi {
+            Some(Interval { lo, hi })
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> i128 {
+        i128::from(self.hi) - i128::from(self.lo) + 1
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+/// What the analysis found out about a program.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// The values each output instruction may output, by pc.
+    pub outputs: BTreeMap<usize, Interval>,
+
+    /// The addresses that may be written, as disjoint ranges in ascending order.
+    pub written: Vec<RangeInclusive<usize>>,
+
+    pub may_halt: bool,
+
+    /// The instructions where runs may fault or the analysis loses track of them, and why.
+    pub incomplete: BTreeMap<usize, String>,
+}
+
+impl Analysis {
+    /// All values the program may output.
+    pub fn output_range(&self) -> Option<Interval> {
+        self.outputs.values().copied().reduce(Interval::join)
+    }
+
+    pub fn may_write(&self, address: usize) -> bool {
+        self.written.iter().any(|r| r.contains(&address))
+    }
+}
+
+/// Runs the abstract interpretation.
+pub struct Analyzer {
+    program: Vec<i64>,
+    isa: Isa,
+    inputs: Vec<Interval>,
+    more_inputs: Interval,
+}
+
+impl Analyzer {
+    pub fn new(program: &[i64]) -> Self {
+        Analyzer {
+            program: program.to_vec(),
+            isa: Isa::Standard,
+            inputs: vec![],
+            more_inputs: Interval::TOP,
+        }
+    }
+
+    pub fn with_isa(mut self, isa: Isa) -> Self {
+        self.isa = isa;
+        self
+    }
+
+    /// The ranges of the first inputs, in order.
+    pub fn with_inputs(mut self, inputs: Vec<Interval>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// The range of the inputs after those given to `with_inputs`; unbounded by default.
+    pub fn with_more_inputs(mut self, range: Interval) -> Self {
+        self.more_inputs = range;
+        self
+    }
+
+    pub fn run(&self) -> Analysis {
+        let mut explorer = Explorer {
+            analyzer: self,
+            report: Analysis::default(),
+            written: BTreeSet::new(),
+        };
+
+        let start = State {
+            rel_base: Value::constant(0),
+            mem: BTreeMap::new(),
+            smeared: None,
+            inputs_read: (0, 0),
+            guards: BTreeMap::new(),
+            calls: vec![],
+        };
+        // states by pc and context
+        let mut states = HashMap::new();
+        let mut depths: HashMap<usize, HashSet<i64>> = HashMap::new();
+        states.insert((0, start.context(&self.program)), (start, 0));
+        let mut queue: VecDeque<_> = states.keys().cloned().collect();
+        let mut queued: HashSet<_> = queue.iter().cloned().collect();
+
+        while let Some(key) = queue.pop_front() {
+            queued.remove(&key);
+            let state = states[&key].0.clone();
+            for (pc, state) in explorer.step(key.0, state) {
+                let next = (pc, state.context(&self.program));
+                if let Some((rel_base, ..)) = next.1 {
+                    let seen = depths.entry(pc).or_default();
+                    if !seen.contains(&rel_base) && seen.len() == MAX_DEPTHS {
+                        let reason =
+                            format!("reached with more than {} relative bases", MAX_DEPTHS);
+                        explorer.report.incomplete.insert(pc, reason);
+                        continue;
+                    }
+                    seen.insert(rel_base);
+                }
+                match states.entry(next.clone()) {
+                    Entry::Vacant(e) => {
+                        e.insert((state, 0));
+                    }
+                    Entry::Occupied(mut e) => {
+                        let (old, joins) = e.get_mut();
+                        let new = old.merge(&state, &self.program, *joins >= WIDEN_DELAY);
+                        if new == *old {
+                            continue;
+                        }
+                        *old = new;
+                        *joins += 1;
+                    }
+                }
+                if queued.insert(next.clone()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut report = explorer.report;
+        for (lo, hi) in explorer.written {
+            match report.written.last_mut() {
+                Some(last) if lo <= last.end() + 1 => {
+                    *last = *last.start()..=hi.max(*last.end());
+                }
+                _ => report.written.push(lo..=hi),
+            }
+        }
+        report
+    }
+
+    /// The possible values of the next input, given how many inputs may have been read.
+    fn input(&self, (lo, hi): (usize, usize)) -> Value {
+        let mut range = None;
+        for k in lo..=hi.min(self.inputs.len()).max(lo) {
+            let input = self.inputs.get(k).copied().unwrap_or(self.more_inputs);
+            range = Some(range.map_or(input, |r: Interval| r.join(input)));
+        }
+        Value::from_interval(range.unwrap())
+    }
+}
+
+/// A set of up to `MAX_SET` values, or an interval.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Value {
+    /// Sorted, without duplicates.
+    Set(Vec<i64>),
+    Range(Interval),
+}
+
+impl Value {
+    const TOP: Value = Value::Range(Interval::TOP);
+
+    fn constant(x: i64) -> Self {
+        Value::Set(vec![x])
+    }
+
+    fn boolean(must: bool, may: bool) -> Self {
+        match (must, may) {
+            (true, _) => Value::constant(1),
+            (false, false) => Value::constant(0),
+            (false, true) => Value::Set(vec![0, 1]),
+        }
+    }
+
+    fn from_interval(range: Interval) -> Self {
+        if range.len() <= MAX_SET as i128 {
+            Value::Set((range.lo..=range.hi).collect())
+        } else {
+            Value::Range(range)
+        }
+    }
+
+    fn from_values(mut values: Vec<i64>) -> Self {
+        values.sort_unstable();
+        values.dedup();
+        if values.len() <= MAX_SET {
+            Value::Set(values)
+        } else {
+            Value::Range(Interval::new(values[0], values[values.len() - 1]))
+        }
+    }
+
+    fn hull(&self) -> Interval {
+        match self {
+            Value::Set(values) => Interval::new(values[0], values[values.len() - 1]),
+            Value::Range(range) => *range,
+        }
+    }
+
+    fn as_constant(&self) -> Option<i64> {
+        match self {
+            Value::Set(values) if values.len() == 1 => Some(values[0]),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, x: i64) -> bool {
+        match self {
+            Value::Set(values) => values.binary_search(&x).is_ok(),
+            Value::Range(range) => range.contains(x),
+        }
+    }
+
+    fn join(&self, other: &Value) -> Value {
+        match (self, other) {
+            (Value::Set(a), Value::Set(b)) => {
+                Value::from_values(a.iter().chain(b).copied().collect())
+            }
+            _ => Value::Range(self.hull().join(other.hull())),
+        }
+    }
+
+    fn widen(&self, newer: &Value) -> Value {
+        match self.join(newer) {
+            Value::Range(range) => Value::Range(self.hull().widen(range)),
+            set => set,
+        }
+    }
+
+    fn add(&self, other: &Value) -> Value {
+        self.combine(other, i64::checked_add)
+    }
+
+    fn mul(&self, other: &Value) -> Value {
+        self.combine(other, i64::checked_mul)
+    }
+
+    /// Apply an addition or multiplication, which returns `None` on overflow.
+    ///
+    /// The VM wraps around on overflow, so an overflow can produce anything.
+    fn combine(&self, other: &Value, f: fn(i64, i64) -> Option<i64>) -> Value {
+        if let (Value::Set(a), Value::Set(b)) = (self, other) {
+            let values: Option<Vec<_>> = a
+                .iter()
+                .flat_map(|&x| b.iter().map(move |&y| f(x, y)))
+                .collect();
+            return values.map_or(Value::TOP, Value::from_values);
+        }
+        // sums and products of intervals take their extremes at the corners
+        let (a, b) = (self.hull(), other.hull());
+        let corners = [(a.lo, b.lo), (a.lo, b.hi), (a.hi, b.lo), (a.hi, b.hi)];
+        let corners: Option<Vec<_>> = corners.iter().map(|&(x, y)| f(x, y)).collect();
+        match corners {
+            Some(c) => Value::from_interval(Interval::new(
+                *c.iter().min().unwrap(),
+                *c.iter().max().unwrap(),
+            )),
+            None => Value::TOP,
+        }
+    }
+
+    fn equals(&self, other: &Value) -> Value {
+        let may = match (self, other) {
+            (Value::Set(a), Value::Set(b)) => a.iter().any(|x| b.binary_search(x).is_ok()),
+            (Value::Set(a), Value::Range(r)) | (Value::Range(r), Value::Set(a)) => {
+                a.iter().any(|&x| r.contains(x))
+            }
+            (Value::Range(a), Value::Range(b)) => a.lo <= b.hi && b.lo <= a.hi,
+        };
+        let must = self.as_constant().is_some() && self.as_constant() == other.as_constant();
+        Value::boolean(must, may)
+    }
+
+    fn less_than(&self, other: &Value) -> Value {
+        let (a, b) = (self.hull(), other.hull());
+        Value::boolean(a.hi < b.lo, a.lo < b.hi)
+    }
+
+    fn may_be_nonzero(&self) -> bool {
+        self.as_constant() != Some(0)
+    }
+
+    /// The values in both, or `None` if there are none.
+    fn meet(&self, other: &Value) -> Option<Value> {
+        match (self, other) {
+            (Value::Set(a), _) => Value::retain(a, |x| other.contains(x)),
+            (_, Value::Set(b)) => Value::retain(b, |x| self.contains(x)),
+            (Value::Range(a), Value::Range(b)) => {
+                let (lo, hi) = (a.lo.max(b.lo), a.hi.min(b.hi));
+                if lo > hi {
+                    return None;
+                }
+                Some(Value::from_interval(Interval::new(lo, hi)))
+            }
+        }
+    }
+
+    /// The values other than `x`; ranges only lose their bounds.
+    fn remove(&self, x: i64) -> Option<Value> {
+        match self {
+            Value::Set(values) => Value::retain(values, |y| y != x),
+            Value::Range(r) if r.lo == x => Some(Value::from_interval(Interval::new(x + 1, r.hi))),
+            Value::Range(r) if r.hi == x => Some(Value::from_interval(Interval::new(r.lo, x - 1))),
+            Value::Range(_) => Some(self.clone()),
+        }
+    }
+
+    fn at_least(&self, lo: i64) -> Option<Value> {
+        self.meet(&Value::Range(Interval::new(lo, i64::MAX)))
+    }
+
+    fn at_most(&self, hi: i64) -> Option<Value> {
+        self.meet(&Value::Range(Interval::new(i64::MIN, hi)))
+    }
+
+    fn retain(values: &[i64], f: impl Fn(i64) -> bool) -> Option<Value> {
+        let values: Vec<_> = values.iter().copied().filter(|&x| f(x)).collect();
+        Some(Value::Set(values)).filter(|v| v != &Value::Set(vec![]))
+    }
+}
+
+/// Where an operand of a comparison came from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Place {
+    Cell(usize),
+    Value(Value),
+}
+
+/// A cell holds the result of comparing `lhs` and `rhs`, which have not changed since.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Guard {
+    less_than: bool,
+    lhs: Place,
+    rhs: Place,
+}
+
+impl Guard {
+    fn reads(&self, address: usize) -> bool {
+        self.lhs == Place::Cell(address) || self.rhs == Place::Cell(address)
+    }
+}
+
+/// The relative base, the innermost calls on the stack and the value at the relative base.
+type Context = (i64, Vec<Option<i64>>, Option<i64>);
+
+/// The abstract machine state at an instruction.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct State {
+    rel_base: Value,
+
+    /// The cells that may differ from the program.
+    mem: BTreeMap<usize, Value>,
+
+    /// After writes to too many cells to track, the cells in the range may also hold the value.
+    smeared: Option<(Interval, Value)>,
+
+    /// The least and the most number of inputs that may have been read.
+    inputs_read: (usize, usize),
+
+    /// Comparisons that decide branches on the cells they were written to.
+    guards: BTreeMap<usize, Guard>,
+
+    /// For each increase of the relative base, the old base and the value of its cell, which
+    /// is where a caller leaves the return address by convention.
+    calls: Vec<(i64, Option<i64>)>,
+}
+
+impl State {
+    /// The relative base, the return addresses of the innermost calls and the value at the
+    /// relative base, which is the return address on entry to a function, if the relative
+    /// base is known.
+    ///
+    /// States are only joined within a context, so that each function call is analyzed
+    /// separately for each return address and depth.
+    fn context(&self, program: &[i64]) -> Option<Context> {
+        let rel_base = self.rel_base.as_constant()?;
+        let calls = self.calls.iter().rev().take(CALL_STRING);
+        let calls = calls.map(|&(_, ret)| ret).collect();
+        Some((rel_base, calls, self.constant_at(program, rel_base)))
+    }
+
+    /// The value at `address`, if it is a valid address and the value is known.
+    fn constant_at(&self, program: &[i64], address: i64) -> Option<i64> {
+        match usize::try_from(address) {
+            Ok(i) if i < MEMORY_SIZE => self.read(program, i).as_constant(),
+            _ => None,
+        }
+    }
+
+    fn read(&self, program: &[i64], address: usize) -> Value {
+        let value = self.cell(program, address);
+        match &self.smeared {
+            Some((range, smeared)) if range.contains(address as i64) => value.join(smeared),
+            _ => value,
+        }
+    }
+
+    fn place(&self, program: &[i64], place: &Place) -> Value {
+        match place {
+            Place::Cell(i) => self.read(program, *i),
+            Place::Value(x) => x.clone(),
+        }
+    }
+
+    /// Forget the guards on and of the cells in `range`.
+    fn invalidate(&mut self, range: RangeInclusive<usize>) {
+        let reads = |p: &Place| matches!(p, Place::Cell(i) if range.contains(i));
+        self.guards
+            .retain(|i, g| !range.contains(i) && !reads(&g.lhs) && !reads(&g.rhs));
+    }
+
+    /// Restrict the state to the runs where the comparison guarding `cell` has the given
+    /// outcome, or return `None` if there are none.
+    fn assume(mut self, program: &[i64], cell: usize, holds: bool) -> Option<State> {
+        let guard = match self.guards.get(&cell) {
+            Some(guard) => guard.clone(),
+            None => return Some(self),
+        };
+        let (a, b) = (
+            self.place(program, &guard.lhs),
+            self.place(program, &guard.rhs),
+        );
+        let (a, b) = match (guard.less_than, holds) {
+            // a < b
+            (true, true) => (
+                a.at_most(b.hull().hi.checked_sub(1)?)?,
+                b.at_least(a.hull().lo.checked_add(1)?)?,
+            ),
+            // a >= b
+            (true, false) => (a.at_least(b.hull().lo)?, b.at_most(a.hull().hi)?),
+            (false, true) => (a.meet(&b)?, b.meet(&a)?),
+            (false, false) => match (a.as_constant(), b.as_constant()) {
+                (Some(x), Some(y)) if x == y => return None,
+                (_, Some(y)) => (a.remove(y)?, b),
+                (Some(x), _) => (a, b.remove(x)?),
+                _ => (a, b),
+            },
+        };
+        for (place, x) in [(&guard.lhs, a), (&guard.rhs, b)] {
+            if let Place::Cell(i) = place {
+                self.mem.insert(*i, x);
+            }
+        }
+        self.mem.insert(cell, Value::boolean(holds, holds));
+        Some(self)
+    }
+
+    fn cell(&self, program: &[i64], address: usize) -> Value {
+        match self.mem.get(&address) {
+            Some(value) => value.clone(),
+            None => Value::constant(program.get(address).copied().unwrap_or(0)),
+        }
+    }
+
+    /// The join of both states, or the widening of `self` by `newer`.
+    fn merge(&self, newer: &State, program: &[i64], widen: bool) -> State {
+        let merge = |a: &Value, b: &Value| if widen { a.widen(b) } else { a.join(b) };
+        let addresses: BTreeSet<usize> = self.mem.keys().chain(newer.mem.keys()).copied().collect();
+        let mem = addresses
+            .into_iter()
+            .map(|i| (i, merge(&self.cell(program, i), &newer.cell(program, i))))
+            .collect();
+
+        let smeared = match (&self.smeared, &newer.smeared) {
+            (Some((r1, v1)), Some((r2, v2))) => {
+                let range = if widen { r1.widen(*r2) } else { r1.join(*r2) };
+                Some((range, merge(v1, v2)))
+            }
+            (Some(s), None) | (None, Some(s)) => Some(s.clone()),
+            (None, None) => None,
+        };
+
+        let ((lo1, hi1), (lo2, hi2)) = (self.inputs_read, newer.inputs_read);
+        let inputs_read = match widen {
+            true if lo2 < lo1 || hi2 > hi1 => (
+                if lo2 < lo1 { 0 } else { lo1 },
+                if hi2 > hi1 { usize::MAX } else { hi1 },
+            ),
+            _ => (lo1.min(lo2), hi1.max(hi2)),
+        };
+
+        let guards = self
+            .guards
+            .iter()
+            .filter(|&(i, g)| newer.guards.get(i) == Some(g))
+            .map(|(i, g)| (*i, g.clone()))
+            .collect();
+
+        State {
+            rel_base: merge(&self.rel_base, &newer.rel_base),
+            mem,
+            smeared,
+            inputs_read,
+            guards,
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+struct Explorer<'a> {
+    analyzer: &'a Analyzer,
+    report: Analysis,
+    written: BTreeSet<(usize, usize)>,
+}
+
+impl<'a> Explorer<'a> {
+    /// Execute the instruction at `pc` and return the states it may lead to.
+    fn step(&mut self, pc: usize, state: State) -> Vec<(usize, State)> {
+        match self.try_step(pc, state) {
+            Ok(next) => next,
+            Err(reason) => {
+                self.report.incomplete.insert(pc, reason);
+                vec![]
+            }
+        }
+    }
+
+    fn try_step(&mut self, pc: usize, mut s: State) -> Result<Vec<(usize, State)>, String> {
+        let (op, len, p) = self.decode(pc, &s)?;
+        let next = pc + len;
+        match op {
+            Op::Add(a, b, c) => {
+                let x = self
+                    .get(&mut s, a, &p[0])?
+                    .add(&self.get(&mut s, b, &p[1])?);
+                self.set(&mut s, c, &p[2], x)?;
+            }
+            Op::Mul(a, b, c) => {
+                let x = self
+                    .get(&mut s, a, &p[0])?
+                    .mul(&self.get(&mut s, b, &p[1])?);
+                self.set(&mut s, c, &p[2], x)?;
+            }
+            Op::Inp(a) => {
+                let x = self.analyzer.input(s.inputs_read);
+                let (lo, hi) = s.inputs_read;
+                s.inputs_read = (lo + 1, hi.saturating_add(1));
+                self.set(&mut s, a, &p[0], x)?;
+            }
+            Op::Out(a) => {
+                let x = self.get(&mut s, a, &p[0])?.hull();
+                let output = self.report.outputs.entry(pc).or_insert(x);
+                *output = output.join(x);
+            }
+            Op::Jit(a, b) | Op::Jif(a, b) => {
+                let cell = self.cell(&s, a, &p[0]);
+                let cond = self.get(&mut s, a, &p[0])?;
+                let target = self.get(&mut s, b, &p[1])?;
+                let (mut jump, mut stay) = (None, None);
+                if cond.may_be_nonzero() {
+                    jump = Some(s.clone());
+                }
+                if cond.contains(0) {
+                    stay = Some(s);
+                }
+                if let Op::Jif(..) = op {
+                    std::mem::swap(&mut jump, &mut stay);
+                }
+                // follow only the runs where the comparison that set the condition agrees
+                if let Some(cell) = cell {
+                    let program = &self.analyzer.program;
+                    let jit = matches!(op, Op::Jit(..));
+                    jump = jump.and_then(|s| s.assume(program, cell, jit));
+                    stay = stay.and_then(|s| s.assume(program, cell, !jit));
+                }
+                return Ok(self.branch(pc, (jump, target), (stay, next)));
+            }
+            Op::Ltn(a, b, c) | Op::Equ(a, b, c) => {
+                let guard = Guard {
+                    less_than: matches!(op, Op::Ltn(..)),
+                    lhs: self.place(&s, a, &p[0]),
+                    rhs: self.place(&s, b, &p[1]),
+                };
+                let (x, y) = (self.get(&mut s, a, &p[0])?, self.get(&mut s, b, &p[1])?);
+                let x = match guard.less_than {
+                    true => x.less_than(&y),
+                    false => x.equals(&y),
+                };
+                let cell = self.cell(&s, c, &p[2]);
+                self.set(&mut s, c, &p[2], x)?;
+                match cell {
+                    Some(i) if !guard.reads(i) => {
+                        s.guards.insert(i, guard);
+                    }
+                    _ => {}
+                }
+            }
+            Op::Crb(a) => {
+                let x = self.get(&mut s, a, &p[0])?;
+                let old = s.rel_base.as_constant();
+                s.rel_base = s.rel_base.add(&x);
+                match (old, s.rel_base.as_constant()) {
+                    (Some(old), Some(new)) if new > old => {
+                        let ret = s.constant_at(&self.analyzer.program, old);
+                        s.calls.push((old, ret));
+                    }
+                    (_, Some(new)) => {
+                        while matches!(s.calls.last(), Some(&(old, _)) if old >= new) {
+                            s.calls.pop();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Op::Halt => {
+                self.report.may_halt = true;
+                return Ok(vec![]);
+            }
+            Op::Ext(..) | Op::Invalid => unreachable!("rejected by decode"),
+        }
+        Ok(vec![(next, s)])
+    }
+
+    fn branch(
+        &mut self,
+        pc: usize,
+        (jump, target): (Option<State>, Value),
+        (stay, next): (Option<State>, usize),
+    ) -> Vec<(usize, State)> {
+        let mut successors = vec![];
+        if let Some(s) = jump {
+            match &target {
+                Value::Set(targets) => {
+                    for &t in targets.iter().filter(|&&t| t >= 0) {
+                        successors.push((t as usize, s.clone()));
+                    }
+                }
+                Value::Range(range) => {
+                    let reason = format!("jump to an address in {}", range);
+                    self.report.incomplete.insert(pc, reason);
+                }
+            }
+        }
+        if let Some(s) = stay {
+            successors.push((next, s));
+        }
+        successors
+    }
+
+    /// Decode the instruction at `pc`; the operands are the values of the parameter cells,
+    /// not those in the returned `Op`.
+    fn decode(&self, pc: usize, s: &State) -> Result<(Op<i64>, usize, Vec<Value>), String> {
+        let program = &self.analyzer.program;
+        if pc + 4 > MEMORY_SIZE {
+            return Err("the pc is out of range".to_string());
+        }
+        let word = s.read(program, pc);
+        let opcode = word
+            .as_constant()
+            .ok_or_else(|| format!("the opcode cell may hold any of {}", word.hull()))?;
+        match Op::<i64>::from_memory_with(&[opcode, 0, 0, 0], self.analyzer.isa) {
+            Some((Op::Invalid, _)) => Err(format!("unknown opcode {}", opcode % 100)),
+            Some((op, len)) => {
+                let params = (pc + 1..pc + len).map(|i| s.read(program, i)).collect();
+                Ok((op, len, params))
+            }
+            None => Err(format!("invalid parameter mode in {}", opcode)),
+        }
+    }
+
+    /// The cell an operand refers to, if it is a single one.
+    fn cell(&self, s: &State, o: Operand<i64>, param: &Value) -> Option<usize> {
+        let address = match o {
+            Operand::Pos(_) => param.clone(),
+            Operand::Rel(_) => s.rel_base.add(param),
+            _ => return None,
+        };
+        address
+            .as_constant()
+            .filter(|&i| i >= 0 && i < MEMORY_SIZE as i64)
+            .map(|i| i as usize)
+    }
+
+    fn place(&self, s: &State, o: Operand<i64>, param: &Value) -> Place {
+        match (o, self.cell(s, o, param)) {
+            (_, Some(i)) => Place::Cell(i),
+            (Operand::Imm(_), None) => Place::Value(param.clone()),
+            _ => Place::Value(Value::TOP),
+        }
+    }
+
+    fn get(&self, s: &mut State, o: Operand<i64>, param: &Value) -> Result<Value, String> {
+        match o {
+            Operand::Imm(_) => Ok(param.clone()),
+            Operand::Pos(_) => self.load(s, param),
+            Operand::Rel(_) => {
+                let address = s.rel_base.add(param);
+                self.load(s, &address)
+            }
+            Operand::Pop => {
+                s.rel_base = s.rel_base.add(&Value::constant(-1));
+                let address = s.rel_base.clone();
+                self.load(s, &address)
+            }
+            Operand::Push => unreachable!("only targets push"),
+        }
+    }
+
+    fn set(
+        &mut self,
+        s: &mut State,
+        o: Operand<i64>,
+        param: &Value,
+        x: Value,
+    ) -> Result<(), String> {
+        match o {
+            Operand::Pos(_) => self.store(s, param, x),
+            Operand::Rel(_) => {
+                let address = s.rel_base.add(param);
+                self.store(s, &address, x)
+            }
+            Operand::Push => {
+                let address = s.rel_base.clone();
+                self.store(s, &address, x)?;
+                s.rel_base = s.rel_base.add(&Value::constant(1));
+                Ok(())
+            }
+            Operand::Imm(_) | Operand::Pop => unreachable!("only sources are immediate or pop"),
+        }
+    }
+
+    /// The valid addresses in `address`, one by one if there are not too many.
+    fn addresses(address: &Value) -> Result<Result<Vec<usize>, Interval>, String> {
+        let out_of_range = || format!("address {} is out of range", address.hull());
+        match address {
+            Value::Set(addresses) => {
+                let valid: Vec<_> = addresses
+                    .iter()
+                    .filter(|&&i| i >= 0 && i < MEMORY_SIZE as i64)
+                    .map(|&i| i as usize)
+                    .collect();
+                if valid.is_empty() {
+                    return Err(out_of_range());
+                }
+                Ok(Ok(valid))
+            }
+            Value::Range(range) => match range.addresses() {
+                Some(r) if r.len() <= MAX_CELLS as i128 => {
+                    Ok(Ok((r.lo..=r.hi).map(|i| i as usize).collect()))
+                }
+                Some(r) => Ok(Err(r)),
+                None => Err(out_of_range()),
+            },
+        }
+    }
+
+    fn load(&self, s: &State, address: &Value) -> Result<Value, String> {
+        let program = &self.analyzer.program;
+        Ok(match Self::addresses(address)? {
+            Ok(addresses) => addresses
+                .into_iter()
+                .map(|i| s.read(program, i))
+                .reduce(|a, b| a.join(&b))
+                .unwrap(),
+            Err(_) => Value::TOP,
+        })
+    }
+
+    fn store(&mut self, s: &mut State, address: &Value, x: Value) -> Result<(), String> {
+        let program = &self.analyzer.program;
+        match Self::addresses(address)? {
+            Ok(addresses) if addresses.len() == 1 => {
+                let i = addresses[0];
+                s.mem.insert(i, x);
+                s.invalidate(i..=i);
+                self.written.insert((i, i));
+            }
+            // any one of the cells is written, the others keep their value
+            Ok(addresses) => {
+                for i in addresses {
+                    let value = s.read(program, i).join(&x);
+                    s.mem.insert(i, value);
+                    s.invalidate(i..=i);
+                    self.written.insert((i, i));
+                }
+            }
+            Err(range) => {
+                s.smeared = Some(match s.smeared.take() {
+                    Some((r, v)) => (r.join(range), v.join(&x)),
+                    None => (range, x),
+                });
+                s.invalidate(range.lo as usize..=range.hi as usize);
+                self.written.insert((range.lo as usize, range.hi as usize));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode2::Computer;
+
+    /// Output 999, 1000 or 1001 if the input is below, equal to or above 8.
+    const COMPARE_TO_8: [i64; 47] = [
+        3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0,
+        1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20,
+        1105, 1, 46, 98, 99,
+    ];
+
+    fn analyze(program: &[i64], input: Interval) -> Analysis {
+        Analyzer::new(program).with_inputs(vec![input]).run()
+    }
+
+    #[test]
+    fn meet_ranges() {
+        let range = |lo, hi| Value::from_interval(Interval::new(lo, hi));
+        assert_eq!(range(0, 1000).meet(&range(2000, 3000)), None);
+        assert_eq!(range(2000, 3000).meet(&range(0, 1000)), None);
+        assert_eq!(
+            range(0, 1000).meet(&range(500, 2000)),
+            Some(range(500, 1000))
+        );
+        assert_eq!(
+            range(0, 1000).meet(&range(1000, 2000)),
+            Some(Value::constant(1000))
+        );
+    }
+
+    #[test]
+    fn both_branches() {
+        let analysis = analyze(&COMPARE_TO_8, Interval::new(0, 100));
+        assert_eq!(analysis.output_range(), Some(Interval::new(999, 1001)));
+        assert!(analysis.may_halt);
+        assert!(analysis.incomplete.is_empty());
+        assert_eq!(analysis.written, vec![20..=21]);
+
+        let analysis = analyze(&COMPARE_TO_8, Interval::new(8, 8));
+        assert_eq!(analysis.output_range(), Some(Interval::constant(1000)));
+        assert_eq!(analysis.outputs.len(), 1);
+
+        let analysis = analyze(&COMPARE_TO_8, Interval::new(-5, 7));
+        assert_eq!(analysis.output_range(), Some(Interval::constant(999)));
+    }
+
+    #[test]
+    fn covers_concrete_runs() {
+        let analysis = analyze(&COMPARE_TO_8, Interval::new(0, 100));
+        for input in 0..=100 {
+            let output = Computer::new(&COMPARE_TO_8).map(std::iter::once(input));
+            for x in output.unwrap() {
+                assert!(analysis.output_range().unwrap().contains(x));
+            }
+        }
+    }
+
+    #[test]
+    fn loops_terminate() {
+        // count to 10
+        let prog = [
+            1101, 0, 1, 20, 4, 20, 1001, 20, 1, 20, 1007, 20, 11, 21, 1005, 21, 4, 99,
+        ];
+        let analysis = Analyzer::new(&prog).run();
+        let outputs = analysis.output_range().unwrap();
+        assert!((1..=10).all(|x| outputs.contains(x)));
+        assert!(analysis.may_halt);
+        assert_eq!(analysis.written, vec![20..=21]);
+
+        // double the input forever
+        let prog = [3, 9, 1002, 9, 2, 9, 1105, 1, 2, 0];
+        let analysis = analyze(&prog, Interval::new(1, 3));
+        assert_eq!(analysis.output_range(), None);
+        assert!(!analysis.may_halt);
+    }
+
+    #[test]
+    fn calls_and_returns() {
+        // call a function that outputs its argument from two sites
+        let prog = [
+            109, 100, 21101, 7, 0, 0, 21101, 13, 0, 1, 1105, 1, 25, 21101, 8, 0, 0, 21101, 24, 0,
+            1, 1105, 1, 25, 99, 204, 0, 2106, 0, 1,
+        ];
+        assert_eq!(
+            Computer::new(&prog).map(std::iter::empty()),
+            Some(vec![7, 8])
+        );
+        let analysis = Analyzer::new(&prog).run();
+        assert_eq!(analysis.output_range(), Some(Interval::new(7, 8)));
+        assert!(analysis.may_halt);
+        assert!(analysis.incomplete.is_empty());
+    }
+
+    #[test]
+    fn lost_track() {
+        // jump to the input
+        let analysis = analyze(&[3, 5, 105, 1, 5, 0], Interval::new(0, 100));
+        assert!(analysis.incomplete[&2].contains("[0, 100]"));
+
+        // overwrite an instruction with the input
+        let analysis = analyze(&[3, 3, 104, 0, 99], Interval::new(0, 100));
+        assert_eq!(analysis.output_range(), Some(Interval::new(0, 100)));
+
+        let analysis = analyze(&[3, 2, 104, 0, 99], Interval::new(0, 100));
+        assert!(analysis.incomplete[&2].contains("may hold any of [0, 100]"));
+        assert!(!analysis.may_halt);
+
+        // recurse forever
+        let analysis = Analyzer::new(&[109, 1, 1105, 1, 0]).run();
+        assert!(analysis.incomplete[&2].contains("more than 8 relative bases"));
+        assert_eq!(analysis.written, vec![]);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::intcode_decompile::analyze;
+use crate::intcode_jit::CompilerContext;
+use cranelift::prelude::{isa, settings, Configurable};
+use cranelift_faerie::{FaerieBackend, FaerieBuilder, FaerieTrapCollection};
+use cranelift_module::{default_libcall_names, Module};
+use std::path::Path;
+
+pub const TARGET: &str = "x86_64-unknown-linux-gnu";
+
+/// Compile a program into a relocatable object file.
+///
+/// The object exports a single C-ABI function named `symbol` with the signature of
+/// `intcode_runtime::IntcodeProgram`, and imports the `intcode_*` runtime functions from
+/// `intcode_runtime`. Link it into any binary that depends on this crate and run it with
+/// `IoContext::run`.
+pub fn compile_object(intcode: &[i64], symbol: &str) -> Vec<u8> {
+    let mut flags = settings::builder();
+    flags.enable("is_pic").unwrap();
+    let isa = isa::lookup_by_name(TARGET)
+        .unwrap()
+        .finish(settings::Flags::new(flags));
+
+    let builder = FaerieBuilder::new(
+        isa,
+        format!("{}.o", symbol),
+        FaerieTrapCollection::Disabled,
+        default_libcall_names(),
+    )
+    .unwrap();
+
+    let mut ctx = CompilerContext::with_module(Module::<FaerieBackend>::new(builder));
+    ctx.define_program(symbol, &analyze(intcode));
+
+    let mut module = ctx.into_module();
+    module.finalize_definitions();
+    module.finish().emit().unwrap()
+}
+
+pub fn write_object(intcode: &[i64], symbol: &str, path: impl AsRef<Path>) -> std::io::Result<()> {
+    std::fs::write(path, compile_object(intcode, symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_elf_object() {
+        let obj = compile_object(&[3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8], "example5_run");
+        assert_eq!(&obj[..4], b"\x7fELF");
+        assert!(contains(&obj, b"example5_run\0"));
+        assert!(contains(&obj, b"intcode_input\0"));
+        assert!(contains(&obj, b"intcode_output\0"));
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+}
@@ -1,20 +1,56 @@
-use crate::intcode2::{Computer, Op, Operand, MEMORY_SIZE};
+use crate::intcode2::{Computer, Isa, Op, Operand, MEMORY_SIZE};
 use crate::intcode_decompile::FixOp::Dynamic;
-use crate::intcode_jit::{Compiler, CompilerContext, IntcodeProgram};
+use crate::intcode_jit::CompilerContext;
+use crate::intcode_runtime::IntcodeProgram;
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
 pub fn compile(intcode: &[i64]) -> IntcodeProgram {
+    compile_with(intcode, Isa::Standard)
+}
+
+pub fn compile_with(intcode: &[i64], isa: Isa) -> IntcodeProgram {
+    compile_vm(&Computer::new(intcode).with_isa(isa))
+}
+
+/// Compile the code in the VM's memory, with the VM's instruction set and extensions.
+pub fn compile_vm(vm: &Computer) -> IntcodeProgram {
+    let blocks = analyze_vm(vm);
+    let mut ctx = CompilerContext::new();
+    ctx.compile_program(&blocks)
+}
+
+/// Split the statically reachable part of a program into basic blocks, keyed by address.
+pub fn analyze(intcode: &[i64]) -> HashMap<usize, Vec<FixOp>> {
+    analyze_with(intcode, Isa::Standard)
+}
+
+pub fn analyze_with(intcode: &[i64], isa: Isa) -> HashMap<usize, Vec<FixOp>> {
+    analyze_vm(&Computer::new(intcode).with_isa(isa))
+}
+
+/// Like `analyze`, for the code in the VM's memory. Extension instructions are left to the
+/// interpreter.
+pub fn analyze_vm(vm: &Computer) -> HashMap<usize, Vec<FixOp>> {
+    let mut vm = vm.clone();
+    vm.pc = 0;
+    vm.rel_base = 0;
     let mut alz = Analyzer {
         mem: vec![CellType::Unknown; MEMORY_SIZE],
         compiled: HashMap::new(),
         op_sizes: HashMap::new(),
-        vm: Computer::new(intcode),
+        vm,
     };
 
     alz.walk();
     let mut keys: Vec<_> = alz.compiled.keys().collect();
     keys.sort();
 
+    // the cells the compiled instructions were decoded from
+    let code_start = keys.first().map_or(0, |&&k| k);
+    let code_end = keys.iter().map(|&&k| k + alz.op_sizes[&k]).max();
+    let code = code_start..code_end.unwrap_or(0);
+
     let mut labels = vec![];
     let mut ops = vec![];
     for k in keys {
@@ -23,24 +59,17 @@ pub fn compile(intcode: &[i64]) -> IntcodeProgram {
     }
 
     let ops = transform(ops);
-
-    for (k, op) in labels.iter().zip(&ops) {
-        println!("{:5}  {:?}", k, op);
-    }
+    let ops = guard_indirect_writes(&labels, ops, code);
 
     let labels = find_used_labels(labels, &ops);
     let blocks = cut_blocks(labels, &ops);
     let blocks = sanitize_blocks(blocks);
 
-    let mut block_labels: Vec<_> = blocks.keys().collect();
-    block_labels.sort();
-
     //println!("{}", rustify(0, &blocks[&0], &blocks));
     //println!("***");
     //println!("{}", rustify(124, &blocks[&124], &blocks));
 
-    let mut ctx = CompilerContext::new();
-    ctx.compile_program(&blocks)
+    blocks
 }
 
 fn build_block(ops: &[FixOp]) -> Vec<String> {
@@ -67,20 +96,41 @@ fn build_operand(p: &Operand<i64>) -> String {
     match p {
         Operand::Imm(i) => i.to_string(),
         Operand::Pos(p) => format!("pos_{}", p),
+        Operand::Pop => "pop()".to_string(),
+        Operand::Push => "*push()".to_string(),
         _ => unimplemented!(),
     }
 }
 
+/// Writes through the relative base, including pushes, may hit the code, which would leave the compiled code
+/// stale. Guard them, so that compiled code leaves the rest of the run to the interpreter if
+/// they do. Ops that only read through the relative base get their address, so that compiled
+/// code can report operands outside memory.
+fn guard_indirect_writes(labels: &[usize], ops: Vec<FixOp>, code: Range<usize>) -> Vec<FixOp> {
+    let indirect = |o: &Operand<i64>| !matches!(o, Operand::Imm(_) | Operand::Pos(_));
+    labels
+        .iter()
+        .zip(ops)
+        .map(|(&pc, op)| match op.operands() {
+            (_, Some(Operand::Rel(_))) | (_, Some(Operand::Push)) => {
+                FixOp::Guarded(pc, code.clone(), Box::new(op))
+            }
+            (sources, _) if sources.iter().any(indirect) => FixOp::At(pc, Box::new(op)),
+            _ => op,
+        })
+        .collect()
+}
+
 fn find_used_labels(labels: Vec<usize>, ops: &[FixOp]) -> Vec<Option<usize>> {
     use Operand::*;
     let mut used = HashSet::new();
     used.insert(0);
     for op in ops {
-        match op {
+        match op.inner() {
             FixOp::Jit(_, label) | FixOp::Jif(_, label) | FixOp::Jmp(label) => {
                 used.insert(*label);
             }
-            FixOp::Set(Imm(label), Rel(0)) => {
+            FixOp::Set(Imm(label), Rel(0)) | FixOp::Set(Imm(label), Push) => {
                 used.insert(*label as usize);
             }
             _ => {}
@@ -117,13 +167,10 @@ fn sanitize_blocks(blocks: HashMap<usize, &[FixOp]>) -> HashMap<usize, Vec<FixOp
         let label = block_labels[i];
         let mut code: Vec<_> = blocks[&label].iter().cloned().collect();
 
-        while let Some(FixOp::Invalid) = code.last() {
-            code.pop();
-        }
-
-        match code.last() {
+        match code.last().map(FixOp::inner) {
             None => {}
             Some(FixOp::Halt) => {}
+            Some(FixOp::Invalid(_)) => {}
             Some(FixOp::Jr0) => {}
             Some(FixOp::Jmp(j)) if *j < i => {
                 // assume a back-jump is always a loop
@@ -131,7 +178,8 @@ fn sanitize_blocks(blocks: HashMap<usize, &[FixOp]>) -> HashMap<usize, Vec<FixOp
                 result.insert(*j, new);
             }
             Some(FixOp::Jmp(_)) => {}
-            _ => code.push(FixOp::Jmp(block_labels[i + 1])),
+            _ if i + 1 < block_labels.len() => code.push(FixOp::Jmp(block_labels[i + 1])),
+            _ => {}
         }
 
         result.insert(label, code);
@@ -248,7 +296,7 @@ impl Analyzer {
     fn walk(&mut self) {
         loop {
             let pc = self.vm.pc;
-            let (op, delta) = self.vm.peek().expect("invalid op");
+            let (op, delta) = self.vm.peek().unwrap_or((Op::Invalid, 0));
             self.vm.pc += delta;
 
             if self.compiled.contains_key(&pc) {
@@ -266,19 +314,13 @@ impl Analyzer {
             }
 
             let fop = match op {
-                _ if dynamic => match op {
-                    Op::Jit(_, _) | Op::Jif(_, _) => {
-                        println!("please, no dynamic jumps!");
-                        FixOp::Dynamic(pc)
-                    }
-                    _ => FixOp::Dynamic(pc),
-                },
+                _ if dynamic => FixOp::Dynamic(pc),
                 Op::Jit(Operand::Imm(1), Operand::Rel(0)) => FixOp::Jr0,
                 Op::Jif(Operand::Imm(0), Operand::Rel(0)) => FixOp::Jr0,
                 //Op::Jit(Operand::Imm(1), Operand::Imm(p)) => FixOp::Jmp(p as usize),
                 //Op::Jif(Operand::Imm(0), Operand::Imm(p)) => FixOp::Jmp(p as usize),
                 Op::Halt => FixOp::Halt,
-                Op::Invalid => FixOp::Invalid,
+                Op::Invalid => FixOp::Invalid(pc),
                 Op::Add(a, b, c) => FixOp::Add(a, b, c),
                 Op::Mul(a, b, c) => FixOp::Mul(a, b, c),
                 Op::Inp(c) => FixOp::Inp(c),
@@ -287,11 +329,10 @@ impl Analyzer {
                 Op::Ltn(a, b, c) => FixOp::Ltn(a, b, c),
                 Op::Jit(a, Operand::Imm(b)) => FixOp::Jit(a, b as usize),
                 Op::Jif(a, Operand::Imm(b)) => FixOp::Jif(a, b as usize),
-                Op::Jit(_, _) | Op::Jif(_, _) => unimplemented!(),
 
                 // unimplemented stuff... fall back to dynamic evaluation
-                Op::Crb(_) => FixOp::Dynamic(pc),
-                //Op::Crb(a) => FixOp::Crb(a),
+                Op::Jit(_, _) | Op::Jif(_, _) => FixOp::Dynamic(pc),
+                Op::Crb(_) | Op::Ext(..) => FixOp::Dynamic(pc),
             };
 
             //println!("{:4}  {:?}", pc, fop);
@@ -300,7 +341,7 @@ impl Analyzer {
             self.op_sizes.insert(pc, delta);
 
             match fop {
-                FixOp::Halt | FixOp::Jr0 | FixOp::Invalid => return,
+                FixOp::Halt | FixOp::Jr0 | FixOp::Invalid(_) => return,
                 FixOp::Out(_) | FixOp::Dynamic(_) => {}
                 FixOp::Set(_, c)
                 | FixOp::Add(_, _, c)
@@ -322,9 +363,6 @@ impl Analyzer {
                     let branch = self.walk();
                     self.vm = vm;
                 }
-                FixOp::Crb(a) => {
-                    self.vm.rel_base += self.get(a) as isize;
-                }
 
                 FixOp::Unknown => println!("ignoring {:?}", fop),
                 _ => unimplemented!("{:?}", fop),
@@ -341,15 +379,6 @@ impl Analyzer {
         }
     }
 
-    fn get(&self, o: Operand<i64>) -> i64 {
-        match o {
-            Operand::Imm(i) => i,
-            Operand::Pos(p) => unimplemented!(),
-            Operand::Rel(o) => unimplemented!(),
-            Operand::Push | Operand::Pop => unimplemented!(),
-        }
-    }
-
     fn mark_constant(&mut self, x: Operand<i64>) -> Option<usize> {
         match x {
             Operand::Imm(_) => {}
@@ -361,10 +390,8 @@ impl Analyzer {
                     self.mem[p] = CellType::Constant;
                 }
             }
-            Operand::Rel(r) => {
-                self.mem[(self.vm.rel_base as isize + r) as usize] = CellType::Constant
-            }
-            Operand::Push | Operand::Pop => unimplemented!(),
+            // the relative base is only known at run time; reads may hit the code
+            Operand::Rel(_) | Operand::Push | Operand::Pop => {}
         }
         None
     }
@@ -381,10 +408,8 @@ impl Analyzer {
                     self.mem[p] = CellType::Mutable;
                 }
             }
-            Operand::Rel(r) => {
-                self.mem[(self.vm.rel_base as isize + r) as usize] = CellType::Mutable
-            }
-            Operand::Push | Operand::Pop => unimplemented!(),
+            // checked at run time, see `guard_indirect_writes`
+            Operand::Rel(_) | Operand::Push | Operand::Pop => {}
         }
         None
     }
@@ -392,7 +417,8 @@ impl Analyzer {
 
 #[derive(Debug, Clone)]
 pub enum FixOp {
-    Invalid,
+    /// The address of an instruction that does not decode.
+    Invalid(usize),
     Halt,
     Add(Operand<i64>, Operand<i64>, Operand<i64>),
     Mul(Operand<i64>, Operand<i64>, Operand<i64>),
@@ -402,16 +428,44 @@ pub enum FixOp {
     Jif(Operand<i64>, usize),
     Ltn(Operand<i64>, Operand<i64>, Operand<i64>),
     Equ(Operand<i64>, Operand<i64>, Operand<i64>),
-    Crb(Operand<i64>),
 
     Set(Operand<i64>, Operand<i64>),
     Jmp(usize),
     Jr0,
     Dynamic(usize),
+    /// The op at the given address, which is interpreted instead if it writes into the code
+    /// in the given range.
+    Guarded(usize, Range<usize>, Box<FixOp>),
+    /// The op at the given address, which reads through the relative base.
+    At(usize, Box<FixOp>),
     Loop,
     Unknown,
 }
 
+impl FixOp {
+    /// The operands that are read, in evaluation order, and the operand that is written.
+    pub fn operands(&self) -> (Vec<Operand<i64>>, Option<Operand<i64>>) {
+        use FixOp::*;
+        match *self {
+            Add(a, b, c) | Mul(a, b, c) | Ltn(a, b, c) | Equ(a, b, c) => (vec![a, b], Some(c)),
+            Set(a, c) => (vec![a], Some(c)),
+            Inp(c) => (vec![], Some(c)),
+            Out(a) | Jit(a, _) | Jif(a, _) => (vec![a], None),
+            Jr0 => (vec![Operand::Rel(0)], None),
+            Guarded(_, _, ref op) | At(_, ref op) => op.operands(),
+            Invalid(_) | Halt | Jmp(_) | Dynamic(_) | Loop | Unknown => (vec![], None),
+        }
+    }
+
+    /// The op without its address or guard.
+    pub fn inner(&self) -> &FixOp {
+        match self {
+            FixOp::Guarded(_, _, op) | FixOp::At(_, op) => op.inner(),
+            op => op,
+        }
+    }
+}
+
 trait Join {
     fn join(self, rhs: Self) -> Self;
 }
@@ -426,6 +480,7 @@ impl<T> Join for Vec<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::intcode_runtime::IoContext;
 
     #[test]
     fn analysis1() {
@@ -433,8 +488,195 @@ mod tests {
         let prog = compile(code);
 
         let mut vm = Computer::new(code);
-        let sr = &mut vm.sr[0] as *mut _;
-        prog(&mut vm, sr);
+        unsafe { IoContext::new(&mut vm).run(prog) }.unwrap();
+
+        let mut expected = Computer::new(code);
+        expected.map(std::iter::empty()).unwrap();
+        assert_eq!(vm.memory(), expected.memory());
+    }
+
+    #[test]
+    fn jit_io_and_branches() {
+        let prog = vec![
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+        for &(input, output) in &[(7, 999), (8, 1000), (9, 1001)] {
+            let out = Computer::new(&prog).map_jit(std::iter::once(input));
+            assert_eq!(out, Some(vec![output]));
+        }
+    }
+
+    #[test]
+    fn jit_relative_base() {
+        let prog = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let out = Computer::new(&prog).map_jit(std::iter::empty());
+        assert_eq!(out, Some(prog));
+    }
+
+    #[test]
+    fn jit_relative_write_into_code() {
+        // overwrite the operand of the output instruction through the relative base
+        let prog = vec![21101, 2, 0, 5, 104, 1, 99];
+        let out = Computer::new(&prog).map_jit(std::iter::empty());
+        assert_eq!(out, Some(vec![2]));
+
+        let prog = vec![109, 200, 21101, 2, 0, 0, 104, 1, 204, 0, 99];
+        let out = Computer::new(&prog).map_jit(std::iter::empty());
+        assert_eq!(out, Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn jit_matches_interpreter() {
+        let code = &INPUT13;
+        let expected = Computer::new(code).map(std::iter::empty());
+        let out = Computer::new(code).map_jit(std::iter::empty());
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn jit_stack_operands() {
+        let programs: [&[i64]; 2] = [
+            &[
+                109, 100, 303, 0, 303, 0, 33301, 0, 0, 0, 31302, 0, 3, 0, 304, 0, 99,
+            ],
+            &[
+                109, 100, 303, 0, 31101, 11, 0, 0, 1105, 1, 20, 304, 0, 99, 0, 0, 0, 0, 0, 0,
+                22202, -2, -2, -2, 3105, 1, 0,
+            ],
+        ];
+        for prog in &programs {
+            let expected = Computer::new(prog)
+                .with_isa(Isa::Extended)
+                .map(vec![4, 5].into_iter());
+            let mut vm = Computer::new(prog).with_isa(Isa::Extended);
+            let out = vm.map_jit(vec![4, 5].into_iter());
+            assert!(expected.is_some());
+            assert_eq!(out, expected);
+            assert_eq!(vm.rel_base, 100);
+        }
+    }
+
+    #[test]
+    fn jit_push_into_code() {
+        // push over the operand of the output instruction right after the push
+        let prog = [109, 7, 31101, 2, 0, 0, 104, 1, 99];
+        let mut vm = Computer::new(&prog).with_isa(Isa::Extended);
+        let out = vm.map_jit(std::iter::empty());
+        assert_eq!(out, Some(vec![2]));
+        assert_eq!(vm.rel_base, 8);
+    }
+
+    #[test]
+    fn jit_extension() {
+        use crate::intcode2::{Extension, Param};
+
+        let square = || {
+            Extension::new(44, &[Param::In, Param::Out], |vm, args| {
+                let x = vm.get(args[0]).unwrap();
+                vm.set(args[1], x * x).unwrap();
+                Ok(None)
+            })
+        };
+        let prog = [3, 30, 44, 30, 31, 4, 31, 99];
+        let out = Computer::new(&prog)
+            .with_extension(square())
+            .map_jit(std::iter::once(7));
+        assert_eq!(out, Some(vec![49]));
+        let out = Computer::new(&prog)
+            .with_extension(square())
+            .map(std::iter::once(7));
+        assert_eq!(out, Some(vec![49]));
+    }
+
+    #[test]
+    fn jit_faults() {
+        use crate::intcode2::{Extension, Fault, FaultKind};
+
+        // reads a second input
+        let prog = [3, 9, 4, 9, 3, 9, 4, 9, 99, 0];
+        let mut vm = Computer::new(&prog);
+        vm.push_input(Some(5));
+        let result = unsafe { IoContext::new(&mut vm).run(compile(&prog)) };
+        assert_eq!(result.unwrap_err().kind, FaultKind::OutOfInput);
+
+        let trap = Extension::new(44, &[], |_, _| Err(FaultKind::Trap(7)));
+        let prog = [104, 1, 44, 104, 2, 99];
+        let mut vm = Computer::new(&prog).with_extension(trap);
+        let prog = compile_vm(&vm);
+        let result = unsafe { IoContext::new(&mut vm).run(prog) };
+        assert_eq!(
+            result,
+            Err(Fault {
+                pc: 2,
+                kind: FaultKind::Trap(7)
+            })
+        );
+    }
+
+    #[test]
+    fn jit_faults_match_interpreter() {
+        use crate::intcode2::WhatsUp;
+
+        let programs: [&[i64]; 5] = [
+            // relative read below memory
+            &[109, -10, 204, 0, 99],
+            // pop from an empty stack
+            &[104, 1, 304, 0, 99],
+            // push past the end of memory
+            &[109, 65534, 104, 1, 31101, 2, 3, 0, 31101, 4, 5, 0, 99],
+            &[104, 1, 42, 99],
+            // invalid opcode at a jump target
+            &[1105, 1, 6, 99, 99, 99, 42],
+        ];
+        for &prog in &programs {
+            let mut vm = Computer::new(prog).with_isa(Isa::Extended);
+            let mut expected_vm = vm.clone();
+            let expected_fault = loop {
+                match expected_vm.run(None) {
+                    Some(WhatsUp::Output(_)) => {}
+                    Some(WhatsUp::Fault(fault)) => break fault,
+                    why => panic!("{:?}", why),
+                }
+            };
+            let compiled = compile_vm(&vm);
+            let result = unsafe { IoContext::new(&mut vm).run(compiled) };
+            assert_eq!(result, Err(expected_fault));
+            assert_eq!(vm.rel_base, expected_vm.rel_base);
+        }
+    }
+
+    #[test]
+    fn jit_jump_out_of_memory() {
+        use crate::intcode2::{Fault, FaultKind};
+
+        let interrupted = |pc: i64| Fault {
+            pc: pc as usize,
+            kind: FaultKind::Interrupted,
+        };
+        let cases: Vec<(&[i64], Fault)> = vec![
+            (
+                &[1105, 1, 65534],
+                Fault {
+                    pc: 65534,
+                    kind: FaultKind::UnknownOpcode(0),
+                },
+            ),
+            (&[1105, 1, 70000], interrupted(70000)),
+            (&[1105, 1, -5], interrupted(-5)),
+            // computed jump to the input
+            (&[3, 7, 105, 1, 7, 99, 99, 0], interrupted(-7)),
+        ];
+        for (prog, fault) in cases {
+            let mut vm = Computer::new(prog);
+            vm.push_input(Some(-7));
+            let compiled = compile_vm(&vm);
+            let result = unsafe { IoContext::new(&mut vm).run(compiled) };
+            assert_eq!(result, Err(fault));
+        }
     }
 
     const INPUT: [i64; 6] = [1, 1, 2, 5, 99, 0];
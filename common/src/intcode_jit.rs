@@ -1,52 +1,82 @@
-use crate::intcode2::{Computer, Op, Operand, MEMORY_SIZE};
+use crate::intcode2::{Operand, MEMORY_SIZE};
 use crate::intcode_decompile::FixOp;
+use crate::intcode_runtime::{
+    intcode_bad_operand, intcode_dynamic, intcode_input, intcode_interpret, intcode_output,
+    intcode_resume, IntcodeProgram, CONTINUE, FAULT, HALT,
+};
 use cranelift::codegen::{ir, Context};
 use cranelift::prelude as cl;
-use cranelift::prelude::{FunctionBuilderContext, InstBuilder, IntCC, Uimm64};
-use cranelift_module::{default_libcall_names, DataId, FuncId, Linkage, Module};
+use cranelift::prelude::{FunctionBuilderContext, InstBuilder, IntCC};
+use cranelift_module::{default_libcall_names, Backend, FuncId, Linkage, Module};
 use cranelift_simplejit::{SimpleJITBackend, SimpleJITBuilder};
 use std::collections::HashMap;
 
-pub type IntcodeProgram = fn(vm: &mut Computer, memory: *mut i64);
-
 pub struct Compiler<'c> {
-    module: &'c mut Module<SimpleJITBackend>,
     builder: cl::FunctionBuilder<'c>,
 
-    dyneval: ir::FuncRef,
+    dynamic: ir::FuncRef,
+    input: ir::FuncRef,
+    output: ir::FuncRef,
+    interpret: ir::FuncRef,
+    resume: ir::FuncRef,
+    bad_operand: ir::FuncRef,
 
-    vm: cl::Value,
+    ctx: cl::Value,
     memory: cl::Value,
+    ptr_type: cl::Type,
+
+    ebbs: HashMap<usize, cl::Ebb>,
+    dispatch_ebb: cl::Ebb,
+    dispatch_used: bool,
+
+    /// The address of the op being compiled, for faults in its indirect operands.
+    pc: usize,
 }
 
 impl<'c> Compiler<'c> {
-    pub fn new(ctx: &'c mut CompilerContext) -> Self {
+    pub fn new<B: Backend>(ctx: &'c mut CompilerContext<B>) -> Self {
+        let ptr_type = ctx.module.target_config().pointer_type();
+
         let mut builder =
             cl::FunctionBuilder::new(&mut ctx.module_context.func, &mut ctx.function_context);
 
         let entry_ebb = builder.create_ebb();
-        let err_ebb = builder.create_ebb();
-        let code_ebb = builder.create_ebb();
+        let dispatch_ebb = builder.create_ebb();
 
         // define entry block
         builder.append_ebb_params_for_function_params(entry_ebb);
         builder.switch_to_block(entry_ebb);
-        let vm = builder.ebb_params(entry_ebb)[0];
+        let vm_ctx = builder.ebb_params(entry_ebb)[0];
         let memory = builder.ebb_params(entry_ebb)[1];
 
-        let dyneval = ctx
+        let dynamic = ctx.module.declare_func_in_func(ctx.dynamic, builder.func);
+        let input = ctx.module.declare_func_in_func(ctx.input, builder.func);
+        let output = ctx.module.declare_func_in_func(ctx.output, builder.func);
+        let interpret = ctx.module.declare_func_in_func(ctx.interpret, builder.func);
+        let resume = ctx.module.declare_func_in_func(ctx.resume, builder.func);
+        let bad_operand = ctx
             .module
-            .declare_func_in_func(ctx.dyneval, &mut builder.func);
+            .declare_func_in_func(ctx.bad_operand, builder.func);
 
         Compiler {
-            module: &mut ctx.module,
-
+            ctx: vm_ctx,
             memory,
-            vm,
+            ptr_type,
 
-            dyneval,
+            dynamic,
+            input,
+            output,
+            interpret,
+            resume,
+            bad_operand,
 
             builder,
+
+            ebbs: HashMap::new(),
+            dispatch_ebb,
+            dispatch_used: false,
+
+            pc: 0,
         }
     }
 
@@ -58,135 +88,470 @@ impl<'c> Compiler<'c> {
     }
 
     pub fn build_blocks(&mut self, blocks: &HashMap<usize, Vec<FixOp>>) {
-        let ebbs: HashMap<_, _> = blocks
+        self.ebbs = blocks
             .keys()
-            .map(|k| (k, self.builder.create_ebb()))
+            .map(|&k| (k, self.builder.create_ebb()))
             .collect();
 
         // there must always be a 0-block
-        self.builder.ins().jump(ebbs[&0], &[]);
+        self.builder.ins().jump(self.ebbs[&0], &[]);
+
+        let mut labels: Vec<_> = blocks.keys().collect();
+        labels.sort();
 
-        for k in blocks.keys() {
-            self.builder.switch_to_block(ebbs[k]);
+        for k in labels {
+            self.builder.switch_to_block(self.ebbs[k]);
+            let mut terminated = false;
             for op in &blocks[k] {
-                match op {
-                    FixOp::Dynamic(ofs) => {
-                        let ofs = self.builder.ins().iconst(cl::types::I64, *ofs as i64);
-                        self.builder.ins().call(self.dyneval, &[self.vm, ofs]);
-                    }
-                    FixOp::Halt => {
-                        self.builder.ins().return_(&[]);
-                    }
-                    FixOp::Set(a, c) => {
-                        let a = self.get_operand(a);
-                        self.set_operand(c, a);
-                    }
-                    FixOp::Add(a, b, c) => {
-                        let a = self.get_operand(a);
-                        let b = self.get_operand(b);
-                        let r = self.builder.ins().iadd(a, b);
-                        self.set_operand(c, r);
-                    }
-                    FixOp::Mul(a, b, c) => {
-                        let a = self.get_operand(a);
-                        let b = self.get_operand(b);
-                        let r = self.builder.ins().imul(a, b);
-                        self.set_operand(c, r);
-                    }
-                    FixOp::Equ(a, b, c) => {
-                        let a = self.get_operand(a);
-                        let b = self.get_operand(b);
-                        let r = self.builder.ins().icmp(IntCC::Equal, a, b);
-                        self.set_operand(c, r);
-                    }
-                    FixOp::Ltn(a, b, c) => {
-                        let a = self.get_operand(a);
-                        let b = self.get_operand(b);
-                        let r = self.builder.ins().icmp(IntCC::SignedLessThan, a, b);
-                        self.set_operand(c, r);
-                    }
-                    _ => unimplemented!("{:?}", op),
+                if self.build_op(op) {
+                    // the remaining ops are unreachable; blocks can only be entered at the top
+                    terminated = true;
+                    break;
                 }
             }
+            if !terminated {
+                // execution ran off the end of the analyzed code; only a block that ends in a
+                // dynamic op, whose instruction was overwritten, can get here
+                self.resume();
+            }
+        }
+
+        if self.dispatch_used {
+            // unreachable jump tables confuse cranelift's dead code elimination
+            self.build_dispatch();
         }
     }
 
-    fn get_operand(&mut self, o: &Operand<i64>) -> cl::Value {
-        match o {
-            Operand::Imm(i) => self.builder.ins().iconst(cl::types::I64, *i),
-            Operand::Pos(p) => self.builder.ins().load(
-                ir::types::I64,
-                cl::MemFlags::new(),
-                self.memory,
-                (*p * std::mem::size_of::<i64>()) as i32,
-            ),
-            _ => unimplemented!("{:?}", o),
+    /// Emit code for a single operation. Returns `true` if the op terminates the block.
+    fn build_op(&mut self, op: &FixOp) -> bool {
+        match op {
+            FixOp::Dynamic(pc) => self.call_dynamic(*pc),
+            FixOp::At(pc, op) => {
+                self.pc = *pc;
+                return self.build_op(op);
+            }
+            FixOp::Guarded(pc, code, op) => {
+                self.pc = *pc;
+                // the relative base only moves by the pops that come before the write
+                let (sources, target) = op.operands();
+                let pops = sources.iter().filter(|o| matches!(o, Operand::Pop)).count();
+                let offset = match target {
+                    Some(Operand::Rel(r)) => r as i64,
+                    _ => 0,
+                } - pops as i64;
+                let rel_base = self.load_rel_base();
+                let idx = self
+                    .builder
+                    .ins()
+                    .iadd_imm(rel_base, offset - code.start as i64);
+                let in_code =
+                    self.builder
+                        .ins()
+                        .icmp_imm(IntCC::UnsignedLessThan, idx, code.len() as i64);
+
+                let interpret_ebb = self.builder.create_ebb();
+                let continue_ebb = self.builder.create_ebb();
+                self.builder.ins().brnz(in_code, interpret_ebb, &[]);
+                self.builder.ins().jump(continue_ebb, &[]);
+
+                self.builder.switch_to_block(interpret_ebb);
+                let pc = self.builder.ins().iconst(cl::types::I64, *pc as i64);
+                self.builder.ins().call(self.interpret, &[self.ctx, pc]);
+                self.builder.ins().return_(&[]);
+
+                self.builder.switch_to_block(continue_ebb);
+                return self.build_op(op);
+            }
+            FixOp::Halt => {
+                self.builder.ins().return_(&[]);
+                return true;
+            }
+            FixOp::Invalid(pc) => {
+                // the runtime reports the fault, or runs whatever was written there since
+                self.call_dynamic(*pc);
+                self.resume();
+                return true;
+            }
+            FixOp::Loop | FixOp::Unknown => {}
+            FixOp::Set(a, c) => {
+                let a = self.get_operand(a);
+                self.set_operand(c, a);
+            }
+            FixOp::Add(a, b, c) => {
+                let a = self.get_operand(a);
+                let b = self.get_operand(b);
+                let r = self.builder.ins().iadd(a, b);
+                self.set_operand(c, r);
+            }
+            FixOp::Mul(a, b, c) => {
+                let a = self.get_operand(a);
+                let b = self.get_operand(b);
+                let r = self.builder.ins().imul(a, b);
+                self.set_operand(c, r);
+            }
+            FixOp::Equ(a, b, c) => {
+                let a = self.get_operand(a);
+                let b = self.get_operand(b);
+                let r = self.builder.ins().icmp(IntCC::Equal, a, b);
+                let r = self.builder.ins().bint(cl::types::I64, r);
+                self.set_operand(c, r);
+            }
+            FixOp::Ltn(a, b, c) => {
+                let a = self.get_operand(a);
+                let b = self.get_operand(b);
+                let r = self.builder.ins().icmp(IntCC::SignedLessThan, a, b);
+                let r = self.builder.ins().bint(cl::types::I64, r);
+                self.set_operand(c, r);
+            }
+            FixOp::Inp(c) => {
+                let call = self.builder.ins().call(self.input, &[self.ctx]);
+                let r = self.builder.inst_results(call)[0];
+
+                // out of input
+                let faulted = self.load_faulted();
+                let stop_ebb = self.builder.create_ebb();
+                let continue_ebb = self.builder.create_ebb();
+                self.builder.ins().brnz(faulted, stop_ebb, &[]);
+                self.builder.ins().jump(continue_ebb, &[]);
+
+                self.builder.switch_to_block(stop_ebb);
+                self.builder.ins().return_(&[]);
+
+                self.builder.switch_to_block(continue_ebb);
+                self.set_operand(c, r);
+            }
+            FixOp::Out(a) => {
+                let a = self.get_operand(a);
+                self.builder.ins().call(self.output, &[self.ctx, a]);
+            }
+            FixOp::Jit(a, label) => {
+                let a = self.get_operand(a);
+                self.branch(a, *label, true);
+            }
+            FixOp::Jif(a, label) => {
+                let a = self.get_operand(a);
+                self.branch(a, *label, false);
+            }
+            FixOp::Jmp(label) => {
+                self.jump_static(*label);
+                return true;
+            }
+            FixOp::Jr0 => {
+                let target = self.get_operand(&Operand::Rel(0));
+                self.jump_dynamic(target);
+                return true;
+            }
         }
+        false
     }
 
-    fn set_operand(&mut self, o: &Operand<i64>, val: cl::Value) {
+    /// Interpret the instruction at `pc`, then continue after it or where it jumped to.
+    fn call_dynamic(&mut self, pc: usize) {
+        let pc = self.builder.ins().iconst(cl::types::I64, pc as i64);
+        let call = self.builder.ins().call(self.dynamic, &[self.ctx, pc]);
+        let next = self.builder.inst_results(call)[0];
+
+        let continue_ebb = self.builder.create_ebb();
+        let jump_ebb = self.builder.create_ebb();
+        let stop_ebb = self.builder.create_ebb();
+
+        let c = self.builder.ins().icmp_imm(IntCC::Equal, next, CONTINUE);
+        self.builder.ins().brnz(c, continue_ebb, &[]);
+        let c = self.builder.ins().icmp_imm(IntCC::Equal, next, HALT);
+        self.builder.ins().brnz(c, stop_ebb, &[]);
+        let c = self.builder.ins().icmp_imm(IntCC::Equal, next, FAULT);
+        self.builder.ins().brnz(c, stop_ebb, &[]);
+        self.builder.ins().jump(jump_ebb, &[]);
+
+        self.builder.switch_to_block(stop_ebb);
+        self.builder.ins().return_(&[]);
+
+        self.builder.switch_to_block(jump_ebb);
+        self.jump_dynamic(next);
+
+        self.builder.switch_to_block(continue_ebb);
+    }
+
+    /// Leave the rest of the run to the interpreter, from where `intcode_dynamic` left off.
+    fn resume(&mut self) {
+        self.builder.ins().call(self.resume, &[self.ctx]);
+        self.builder.ins().return_(&[]);
+    }
+
+    fn branch(&mut self, cond: cl::Value, label: usize, if_true: bool) {
+        let ebb = match self.ebbs.get(&label) {
+            Some(&ebb) => ebb,
+            None => {
+                // jump to an address without native code, go through the dispatcher
+                let ebb = self.builder.create_ebb();
+                let continue_ebb = self.builder.create_ebb();
+                if if_true {
+                    self.builder.ins().brnz(cond, ebb, &[]);
+                } else {
+                    self.builder.ins().brz(cond, ebb, &[]);
+                }
+                self.builder.ins().jump(continue_ebb, &[]);
+                self.builder.switch_to_block(ebb);
+                self.jump_static(label);
+                self.builder.switch_to_block(continue_ebb);
+                return;
+            }
+        };
+        if if_true {
+            self.builder.ins().brnz(cond, ebb, &[]);
+        } else {
+            self.builder.ins().brz(cond, ebb, &[]);
+        }
+    }
+
+    fn jump_static(&mut self, label: usize) {
+        match self.ebbs.get(&label) {
+            Some(&ebb) => {
+                self.builder.ins().jump(ebb, &[]);
+            }
+            None => {
+                let target = self.builder.ins().iconst(cl::types::I64, label as i64);
+                self.jump_dynamic(target);
+            }
+        }
+    }
+
+    fn jump_dynamic(&mut self, target: cl::Value) {
+        self.dispatch_used = true;
+        self.builder.ins().jump(self.dispatch_ebb, &[target]);
+    }
+
+    /// Build the jump table that maps intcode addresses to native blocks.
+    /// Addresses without a block continue in the interpreter.
+    fn build_dispatch(&mut self) {
+        let fallback_ebb = self.builder.create_ebb();
+
+        // blocks for jumps out of memory are only entered statically
+        let labels = self.ebbs.keys().filter(|&&k| k < MEMORY_SIZE);
+        let n = labels.max().map(|&k| k + 1).unwrap_or(0);
+        let mut table = ir::JumpTableData::with_capacity(n);
+        for pc in 0..n {
+            table.push_entry(*self.ebbs.get(&pc).unwrap_or(&fallback_ebb));
+        }
+        let table = self.builder.create_jump_table(table);
+
+        // jump tables can't pass ebb arguments, so the fallback reads the target from the stack
+        let slot = self.builder.create_stack_slot(ir::StackSlotData::new(
+            ir::StackSlotKind::ExplicitSlot,
+            std::mem::size_of::<i64>() as u32,
+        ));
+
+        self.builder.switch_to_block(self.dispatch_ebb);
+        let target = self
+            .builder
+            .append_ebb_param(self.dispatch_ebb, cl::types::I64);
+        self.builder.ins().stack_store(target, slot, 0);
+        self.builder.ins().br_table(target, fallback_ebb, table);
+
+        self.builder.switch_to_block(fallback_ebb);
+        let target = self.builder.ins().stack_load(cl::types::I64, slot, 0);
+        self.builder.ins().call(self.interpret, &[self.ctx, target]);
+        self.builder.ins().return_(&[]);
+    }
+
+    fn address(&mut self, o: &Operand<i64>) -> (cl::Value, i32) {
         match o {
-            Operand::Imm(i) => panic!("write to immediate"),
             Operand::Pos(p) => {
-                self.builder.ins().store(
-                    cl::MemFlags::new(),
-                    val,
-                    self.memory,
-                    (*p * std::mem::size_of::<i64>()) as i32,
-                );
-            }
-            _ => unimplemented!("{:?}", o),
+                assert!(*p < MEMORY_SIZE, "address out of range: {}", p);
+                (self.memory, (*p * std::mem::size_of::<i64>()) as i32)
+            }
+            Operand::Rel(r) => {
+                let rel_base = self.load_rel_base();
+                let idx = self.builder.ins().iadd_imm(rel_base, *r as i64);
+                (self.index(idx), 0)
+            }
+            // the relative base is the stack pointer and points just past the topmost value
+            // the relative base stays put if the address is out of range
+            Operand::Pop => {
+                let rel_base = self.load_rel_base();
+                let idx = self.builder.ins().iadd_imm(rel_base, -1);
+                let address = self.index(idx);
+                self.store_rel_base(idx);
+                (address, 0)
+            }
+            Operand::Push => {
+                let idx = self.load_rel_base();
+                let address = self.index(idx);
+                let rel_base = self.builder.ins().iadd_imm(idx, 1);
+                self.store_rel_base(rel_base);
+                (address, 0)
+            }
+            Operand::Imm(_) => {
+                unreachable!("immediate operands are never read or written through an address")
+            }
+        }
+    }
+
+    /// The address of memory cell `idx`. If it is out of range, the current op faults.
+    fn index(&mut self, idx: cl::Value) -> cl::Value {
+        let oob =
+            self.builder
+                .ins()
+                .icmp_imm(IntCC::UnsignedGreaterThanOrEqual, idx, MEMORY_SIZE as i64);
+        let fault_ebb = self.builder.create_ebb();
+        let continue_ebb = self.builder.create_ebb();
+        self.builder.ins().brnz(oob, fault_ebb, &[]);
+        self.builder.ins().jump(continue_ebb, &[]);
+
+        self.builder.switch_to_block(fault_ebb);
+        let pc = self.builder.ins().iconst(cl::types::I64, self.pc as i64);
+        self.builder.ins().call(self.bad_operand, &[self.ctx, pc]);
+        self.builder.ins().return_(&[]);
+
+        self.builder.switch_to_block(continue_ebb);
+        let ofs = self.builder.ins().ishl_imm(idx, 3);
+        let ofs = self.ptr_sized(ofs);
+        self.builder.ins().iadd(self.memory, ofs)
+    }
+
+    fn load_rel_base(&mut self) -> cl::Value {
+        self.builder
+            .ins()
+            .load(cl::types::I64, cl::MemFlags::trusted(), self.ctx, 0)
+    }
+
+    fn store_rel_base(&mut self, rel_base: cl::Value) {
+        self.builder
+            .ins()
+            .store(cl::MemFlags::trusted(), rel_base, self.ctx, 0);
+    }
+
+    fn load_faulted(&mut self) -> cl::Value {
+        self.builder
+            .ins()
+            .load(cl::types::I64, cl::MemFlags::trusted(), self.ctx, 8)
+    }
+
+    fn ptr_sized(&mut self, x: cl::Value) -> cl::Value {
+        if self.ptr_type == cl::types::I64 {
+            x
+        } else {
+            self.builder.ins().ireduce(self.ptr_type, x)
+        }
+    }
+
+    fn get_operand(&mut self, o: &Operand<i64>) -> cl::Value {
+        match o {
+            Operand::Imm(i) => self.builder.ins().iconst(cl::types::I64, *i),
+            _ => {
+                let (base, offset) = self.address(o);
+                self.builder
+                    .ins()
+                    .load(cl::types::I64, cl::MemFlags::new(), base, offset)
+            }
         }
     }
-}
 
-unsafe fn dyneval(vm: &mut Computer, offset: usize) {
-    match vm.peek_at(offset) {
-        None => println!(" *** invalid dynamic operation *** "),
-        Some((op, _)) => {
-            if let Some(r) = vm.apply(op) {
-                println!("Unsupported dynamic return value: {:?}", r);
+    fn set_operand(&mut self, o: &Operand<i64>, val: cl::Value) {
+        match o {
+            Operand::Imm(_) => panic!("write to immediate"),
+            _ => {
+                let (base, offset) = self.address(o);
+                self.builder
+                    .ins()
+                    .store(cl::MemFlags::new(), val, base, offset);
             }
         }
     }
 }
 
-pub struct CompilerContext {
-    module: Module<SimpleJITBackend>,
+pub struct CompilerContext<B: Backend = SimpleJITBackend> {
+    module: Module<B>,
     module_context: Context,
     function_context: FunctionBuilderContext,
 
-    dyneval: FuncId,
+    dynamic: FuncId,
+    input: FuncId,
+    output: FuncId,
+    interpret: FuncId,
+    resume: FuncId,
+    bad_operand: FuncId,
 }
 
-impl CompilerContext {
+impl Default for CompilerContext<SimpleJITBackend> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompilerContext<SimpleJITBackend> {
     pub fn new() -> Self {
-        let mut module = {
-            let mut jit_builder = SimpleJITBuilder::new(default_libcall_names());
-            jit_builder.symbol("dyneval", dyneval as *const u8);
-            /*for (name, func) in dictionary {
-                jit_builder.symbol(name.as_str(), *func as *const u8);
-            }*/
-            //runtime::init_symbols(&mut jit_builder);
-            Module::new(jit_builder)
-        };
+        let mut jit_builder = SimpleJITBuilder::new(default_libcall_names());
+        jit_builder.symbol("intcode_dynamic", intcode_dynamic as *const u8);
+        jit_builder.symbol("intcode_input", intcode_input as *const u8);
+        jit_builder.symbol("intcode_output", intcode_output as *const u8);
+        jit_builder.symbol("intcode_interpret", intcode_interpret as *const u8);
+        jit_builder.symbol("intcode_resume", intcode_resume as *const u8);
+        jit_builder.symbol("intcode_bad_operand", intcode_bad_operand as *const u8);
+        Self::with_module(Module::new(jit_builder))
+    }
+
+    pub fn compile_program(&mut self, blocks: &HashMap<usize, Vec<FixOp>>) -> IntcodeProgram {
+        let func = self.define_program("function", blocks);
+
+        self.module.finalize_definitions();
+        let raw_code = self.module.get_finalized_function(func);
+        // converting a raw pointer to the compiled function to a typed Rust function pointer
+        // is inherently unsafe because there is no way for the compiler to verify the function
+        // signature, or to determine what the function does is actually safe.
+        unsafe { std::mem::transmute(raw_code) }
+    }
+}
+
+impl<B: Backend> CompilerContext<B> {
+    /// Set up a compiler context that declares the runtime functions in `module`.
+    pub fn with_module(mut module: Module<B>) -> Self {
         let module_context = module.make_context();
         let function_context = cl::FunctionBuilderContext::new();
+        let ptr = module.target_config().pointer_type();
+
+        let mut signature = module.make_signature();
+        signature.params.push(cl::AbiParam::new(ptr));
+        signature.params.push(cl::AbiParam::new(cl::types::I64));
+        signature.returns.push(cl::AbiParam::new(cl::types::I64));
+        let dynamic = module
+            .declare_function("intcode_dynamic", Linkage::Import, &signature)
+            .unwrap();
+
+        let mut signature = module.make_signature();
+        signature.params.push(cl::AbiParam::new(ptr));
+        signature.returns.push(cl::AbiParam::new(cl::types::I64));
+        let input = module
+            .declare_function("intcode_input", Linkage::Import, &signature)
+            .unwrap();
 
-        let mut dyneval_signature = module.make_signature();
-        dyneval_signature
-            .params
-            .push(cl::AbiParam::new(cl::types::I64));
-        dyneval_signature
-            .params
-            .push(cl::AbiParam::new(cl::types::I64));
+        let mut signature = module.make_signature();
+        signature.params.push(cl::AbiParam::new(ptr));
+        signature.params.push(cl::AbiParam::new(cl::types::I64));
+        let output = module
+            .declare_function("intcode_output", Linkage::Import, &signature)
+            .unwrap();
 
-        let dyneval = module
-            .declare_function("dyneval", Linkage::Import, &dyneval_signature)
+        let interpret = module
+            .declare_function("intcode_interpret", Linkage::Import, &signature)
+            .unwrap();
+        let bad_operand = module
+            .declare_function("intcode_bad_operand", Linkage::Import, &signature)
+            .unwrap();
+
+        let mut signature = module.make_signature();
+        signature.params.push(cl::AbiParam::new(ptr));
+        let resume = module
+            .declare_function("intcode_resume", Linkage::Import, &signature)
             .unwrap();
 
         CompilerContext {
-            dyneval,
+            dynamic,
+            input,
+            output,
+            interpret,
+            resume,
+            bad_operand,
 
             module,
             module_context,
@@ -194,12 +559,13 @@ impl CompilerContext {
         }
     }
 
-    pub fn compile_program(&mut self, blocks: &HashMap<usize, Vec<FixOp>>) -> IntcodeProgram {
+    /// Compile the program into an exported function called `name`.
+    pub fn define_program(&mut self, name: &str, blocks: &HashMap<usize, Vec<FixOp>>) -> FuncId {
         let signature = Self::intcode_program_signature(&mut self.module);
 
         let func = self
             .module
-            .declare_function("function", Linkage::Export, &signature)
+            .declare_function(name, Linkage::Export, &signature)
             .unwrap();
 
         self.module_context.func.signature = signature;
@@ -213,19 +579,18 @@ impl CompilerContext {
             .unwrap();
         self.module.clear_context(&mut self.module_context);
 
-        self.module.finalize_definitions();
-        let raw_code = self.module.get_finalized_function(func);
-        // converting a raw pointer to the compiled function to a typed Rust function pointer
-        // is inherently unsafe because there is no way for the compiler to verify the function
-        // signature, or to determine what the function does is actually safe.
-        let code_ptr = unsafe { std::mem::transmute(raw_code) };
-        code_ptr
+        func
+    }
+
+    pub fn into_module(self) -> Module<B> {
+        self.module
     }
 
-    pub fn intcode_program_signature(module: &mut Module<SimpleJITBackend>) -> cl::Signature {
+    pub fn intcode_program_signature(module: &mut Module<B>) -> cl::Signature {
+        let ptr = module.target_config().pointer_type();
         let mut sig = module.make_signature();
-        sig.params.push(cl::AbiParam::new(cl::types::I64));
-        sig.params.push(cl::AbiParam::new(cl::types::I64));
+        sig.params.push(cl::AbiParam::new(ptr));
+        sig.params.push(cl::AbiParam::new(ptr));
         sig
     }
 }
@@ -0,0 +1,202 @@
+//! Runtime support for natively compiled intcode programs.
+//!
+//! Code produced by the JIT (`intcode_jit`) and by the object file backend (`intcode_aot`)
+//! calls back into the functions below for input, output and every instruction the
+//! compiler could not translate statically. The functions have C linkage so that object
+//! files can be linked against them like against any other C library.
+
+use crate::intcode2::{Computer, Fault, FaultKind, WhatsUp};
+
+/// Entry point of a compiled intcode program.
+pub type IntcodeProgram = unsafe extern "C" fn(ctx: *mut IoContext, memory: *mut i64);
+
+/// `intcode_dynamic` result: continue with the next instruction.
+pub const CONTINUE: i64 = -1;
+
+/// `intcode_dynamic` result: the program halted.
+pub const HALT: i64 = -2;
+
+/// `intcode_dynamic` result: the program faulted, see `IoContext::run`.
+pub const FAULT: i64 = -3;
+
+/// State shared between compiled code and the runtime.
+///
+/// Compiled code treats this as opaque, except for the relative base at offset 0, which it
+/// reads and, for stack operands, updates, and the fault flag at offset 8, which it checks
+/// after every input. The runtime syncs the relative base with the VM on every call.
+#[repr(C)]
+pub struct IoContext<'a> {
+    rel_base: i64,
+    /// Nonzero once `fault` is set.
+    faulted: i64,
+    vm: &'a mut Computer,
+    output: Vec<i64>,
+    fault: Option<Fault>,
+}
+
+impl<'a> IoContext<'a> {
+    pub fn new(vm: &'a mut Computer) -> Self {
+        IoContext {
+            rel_base: vm.rel_base as i64,
+            faulted: 0,
+            vm,
+            output: vec![],
+            fault: None,
+        }
+    }
+
+    /// Run a compiled program on the context's VM and return its output.
+    ///
+    /// All input must have been queued in the VM beforehand: compiled code cannot be
+    /// suspended, so running out of input is a `FaultKind::OutOfInput` fault. Compiled code
+    /// does not track the pc, so that fault has the pc the VM was last left at.
+    ///
+    /// # Safety
+    /// `program` must have been compiled from the code currently in the VM's memory.
+    pub unsafe fn run(mut self, program: IntcodeProgram) -> Result<Vec<i64>, Fault> {
+        self.rel_base = self.vm.rel_base as i64;
+        let memory = self.vm.memory_mut().as_mut_ptr();
+        program(&mut self, memory);
+        self.vm.rel_base = self.rel_base as isize;
+        self.vm.flush_decoded();
+        match self.fault {
+            Some(fault) => Err(fault),
+            None => Ok(self.output),
+        }
+    }
+
+    /// Record why the interpreter stopped at `pc` without halting; compiled code returns as
+    /// soon as it sees the flag.
+    fn stop(&mut self, pc: usize, why: Option<WhatsUp<i64>>) {
+        let fault = match why {
+            Some(WhatsUp::Fault(fault)) => fault,
+            Some(WhatsUp::NeedInput) => Fault {
+                pc,
+                kind: FaultKind::OutOfInput,
+            },
+            _ => Fault {
+                pc,
+                kind: FaultKind::Interrupted,
+            },
+        };
+        self.fault = Some(fault);
+        self.faulted = 1;
+    }
+}
+
+/// Read the next input value; if there is none, set the fault flag and return 0.
+///
+/// # Safety
+/// `ctx` must point to a live `IoContext`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_input(ctx: *mut IoContext) -> i64 {
+    let ctx = &mut *ctx;
+    match ctx.vm.next_input() {
+        Some(x) => x,
+        None => {
+            ctx.stop(ctx.vm.pc, Some(WhatsUp::NeedInput));
+            0
+        }
+    }
+}
+
+/// Emit an output value.
+///
+/// # Safety
+/// `ctx` must point to a live `IoContext`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_output(ctx: *mut IoContext, value: i64) {
+    (*ctx).output.push(value);
+}
+
+/// Interpret the single instruction at `pc`.
+///
+/// Returns `CONTINUE` if execution proceeds with the following instruction, `HALT` if the
+/// program halted, `FAULT` if it faulted, or the new pc after a jump.
+///
+/// # Safety
+/// `ctx` must point to a live `IoContext`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_dynamic(ctx: *mut IoContext, pc: i64) -> i64 {
+    let ctx = &mut *ctx;
+    ctx.vm.rel_base = ctx.rel_base as isize;
+    let pc = pc as usize;
+    let (op, delta) = match ctx.vm.peek_at(pc) {
+        Some(x) => x,
+        None => {
+            ctx.vm.pc = pc;
+            let why = ctx.vm.fetch_fault();
+            ctx.stop(pc, why);
+            return FAULT;
+        }
+    };
+    ctx.vm.pc = pc + delta;
+    let next = match ctx.vm.apply(op) {
+        None if ctx.vm.pc == pc + delta => CONTINUE,
+        None => ctx.vm.pc as i64,
+        Some(Some(WhatsUp::Output(x))) => {
+            ctx.output.push(x);
+            CONTINUE
+        }
+        Some(Some(WhatsUp::Halt)) => HALT,
+        // only `run` enforces budgets
+        Some(Some(WhatsUp::BudgetExhausted)) | Some(Some(WhatsUp::InfiniteLoop)) => unreachable!(),
+        Some(why) => {
+            ctx.stop(pc, why);
+            FAULT
+        }
+    };
+    ctx.rel_base = ctx.vm.rel_base as i64;
+    next
+}
+
+/// Report an operand of the instruction at `pc` that addresses a cell outside memory.
+///
+/// # Safety
+/// `ctx` must point to a live `IoContext`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_bad_operand(ctx: *mut IoContext, pc: i64) {
+    let fault = Fault {
+        pc: pc as usize,
+        kind: FaultKind::BadOperand,
+    };
+    (*ctx).stop(pc as usize, Some(WhatsUp::Fault(fault)));
+}
+
+/// Finish the program in the interpreter, starting at `pc`.
+///
+/// Compiled code calls this when it jumps to an address it has no native code for.
+///
+/// # Safety
+/// `ctx` must point to a live `IoContext`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_interpret(ctx: *mut IoContext, pc: i64) {
+    let ctx = &mut *ctx;
+    ctx.vm.rel_base = ctx.rel_base as isize;
+    ctx.vm.pc = pc as usize;
+    // compiled code writes to memory directly
+    ctx.vm.flush_decoded();
+    loop {
+        match ctx.vm.run(None) {
+            Some(WhatsUp::Halt) => break,
+            Some(WhatsUp::Output(x)) => ctx.output.push(x),
+            why => {
+                ctx.stop(ctx.vm.pc, why);
+                break;
+            }
+        }
+    }
+    ctx.rel_base = ctx.vm.rel_base as i64;
+}
+
+/// Finish the program in the interpreter, where the last `intcode_dynamic` call left off.
+///
+/// Compiled code calls this when it runs past the end of the code it was compiled from.
+///
+/// # Safety
+/// `ctx` must point to a live `IoContext`.
+#[no_mangle]
+pub unsafe extern "C" fn intcode_resume(ctx: *mut IoContext) {
+    let pc = (*ctx).vm.pc as i64;
+    intcode_interpret(ctx, pc);
+}
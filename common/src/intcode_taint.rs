@@ -0,0 +1,366 @@
+//! Taint tracking for intcode programs.
+//!
+//! Each input carries a set of labels, and every value computed from it carries them too:
+//! through arithmetic, comparisons, memory and the addresses used to access it, including
+//! the relative base. Optionally, values also carry the labels of the branches that led to
+//! them (control dependencies). Without knowing where the paths of a branch meet again,
+//! these labels stick to everything the program does afterwards, so they are a coarse
+//! upper bound.
+
+use crate::intcode2::{
+    Computable, ComputerImpl, ExtResult, Fault, FaultKind, Op, Operand, WhatsUp,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops;
+
+/// A set of labels from 0 to 63.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Labels(u64);
+
+impl Labels {
+    pub const EMPTY: Labels = Labels(0);
+
+    pub fn single(label: u32) -> Self {
+        assert!(label < 64, "label {} is out of range", label);
+        Labels(1 << label)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, label: u32) -> bool {
+        label < 64 && self.0 & 1 << label != 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..64).filter(move |&label| self.contains(label))
+    }
+}
+
+impl ops::BitOr for Labels {
+    type Output = Labels;
+    fn bitor(self, rhs: Self) -> Self {
+        Labels(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for Labels {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Display for Labels {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let labels: Vec<_> = self.iter().map(|label| label.to_string()).collect();
+        write!(f, "{{{}}}", labels.join(", "))
+    }
+}
+
+/// A value and the labels of the inputs it was computed from.
+///
+/// Arithmetic wraps around like with `i64`. Comparisons only look at the values, as the VM
+/// does.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Tainted {
+    pub value: i64,
+    pub labels: Labels,
+}
+
+impl Tainted {
+    pub fn new(value: i64, labels: Labels) -> Self {
+        Tainted { value, labels }
+    }
+
+    fn with(self, labels: Labels) -> Self {
+        Tainted::new(self.value, self.labels | labels)
+    }
+}
+
+impl Computable for Tainted {
+    fn as_i64(&self) -> i64 {
+        self.value
+    }
+}
+
+impl From<i64> for Tainted {
+    fn from(value: i64) -> Self {
+        Tainted::new(value, Labels::EMPTY)
+    }
+}
+
+impl ops::Add for Tainted {
+    type Output = Tainted;
+    fn add(self, rhs: Self) -> Self {
+        Tainted::new(self.value.wrapping_add(rhs.value), self.labels).with(rhs.labels)
+    }
+}
+
+impl ops::Mul for Tainted {
+    type Output = Tainted;
+    fn mul(self, rhs: Self) -> Self {
+        Tainted::new(self.value.wrapping_mul(rhs.value), self.labels).with(rhs.labels)
+    }
+}
+
+impl PartialEq for Tainted {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for Tainted {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+/// Runs a program and tracks which inputs the outputs and memory cells depend on.
+pub struct Tracker {
+    vm: ComputerImpl<Tainted>,
+    /// The labels of the relative base.
+    rel_base: Labels,
+    /// The labels of the branches taken so far, if control dependencies are tracked.
+    control: Option<Labels>,
+}
+
+impl Tracker {
+    pub fn new(program: &[i64]) -> Self {
+        Tracker {
+            vm: ComputerImpl::new(program),
+            rel_base: Labels::EMPTY,
+            control: None,
+        }
+    }
+
+    /// Also label values with the labels of the branches and jumps that led to them.
+    pub fn with_control_dependencies(mut self) -> Self {
+        self.control = Some(Labels::EMPTY);
+        self
+    }
+
+    pub fn push_input(&mut self, input: impl IntoIterator<Item = Tainted>) {
+        self.vm.push_input(input);
+    }
+
+    /// The cells whose values depend on labelled inputs, with their labels.
+    pub fn tainted_cells(&self) -> BTreeMap<usize, Labels> {
+        let cells = self.vm.memory().iter().enumerate();
+        cells
+            .filter(|(_, x)| !x.labels.is_empty())
+            .map(|(i, x)| (i, x.labels))
+            .collect()
+    }
+
+    pub fn memory(&self) -> &[Tainted] {
+        self.vm.memory()
+    }
+
+    /// Run the program on the given input and return its output.
+    pub fn map(&mut self, input: impl IntoIterator<Item = Tainted>) -> Vec<Tainted> {
+        self.push_input(input);
+        let mut output = vec![];
+        loop {
+            match self.run() {
+                Some(WhatsUp::Halt) => break,
+                Some(WhatsUp::Output(x)) => output.push(x),
+                Some(WhatsUp::NeedInput) => panic!("out of input values"),
+                Some(WhatsUp::Fault(f)) => panic!("{}", f),
+                r => panic!("unexpected {:?}", r),
+            }
+        }
+        output
+    }
+
+    /// Run until the program halts, faults, outputs a value or needs more input.
+    pub fn run(&mut self) -> Option<WhatsUp<Tainted>> {
+        loop {
+            let pc = self.vm.pc;
+            let op = match self.vm.fetch() {
+                Some(op) => op,
+                None => return self.vm.fetch_fault(),
+            };
+            // the labels of the parameter cells, which taint the addresses they hold
+            let params: Vec<_> = (pc + 1..self.vm.pc)
+                .map(|i| self.vm.memory()[i].labels)
+                .collect();
+            if let Some(control) = &mut self.control {
+                *control |= self.vm.memory()[pc].labels;
+            }
+            match self.apply(op, &params) {
+                Ok(None) => {}
+                Ok(Some(WhatsUp::NeedInput)) => {
+                    self.vm.pc = pc;
+                    return Some(WhatsUp::NeedInput);
+                }
+                Ok(Some(r)) => return Some(r),
+                Err(kind) => return Some(WhatsUp::Fault(Fault { pc, kind })),
+            }
+        }
+    }
+
+    fn apply(&mut self, op: Op<Tainted>, p: &[Labels]) -> ExtResult<Tainted> {
+        match op {
+            Op::Add(a, b, c) => {
+                let x = self.get(a, p[0])? + self.get(b, p[1])?;
+                self.set(c, p[2], x)?;
+            }
+            Op::Mul(a, b, c) => {
+                let x = self.get(a, p[0])? * self.get(b, p[1])?;
+                self.set(c, p[2], x)?;
+            }
+            Op::Inp(a) => match self.vm.next_input() {
+                Some(x) => self.set(a, p[0], x)?,
+                None => return Ok(Some(WhatsUp::NeedInput)),
+            },
+            Op::Out(a) => {
+                let x = self.get(a, p[0])?;
+                return Ok(Some(WhatsUp::Output(
+                    x.with(self.control.unwrap_or_default()),
+                )));
+            }
+            Op::Jit(a, b) | Op::Jif(a, b) => {
+                let (a, b) = (self.get(a, p[0])?, self.get(b, p[1])?);
+                let jump = match op {
+                    Op::Jit(..) => a.value != 0,
+                    _ => a.value == 0,
+                };
+                if let Some(control) = &mut self.control {
+                    *control |= a.labels;
+                    if jump {
+                        *control |= b.labels;
+                    }
+                }
+                if jump {
+                    self.vm.pc = b.value as usize;
+                }
+            }
+            Op::Ltn(a, b, c) | Op::Equ(a, b, c) => {
+                let (a, b) = (self.get(a, p[0])?, self.get(b, p[1])?);
+                let holds = match op {
+                    Op::Ltn(..) => a.value < b.value,
+                    _ => a.value == b.value,
+                };
+                self.set(c, p[2], Tainted::new(holds as i64, a.labels | b.labels))?;
+            }
+            Op::Crb(a) => {
+                let x = self.get(a, p[0])?;
+                self.vm.rel_base += x.value as isize;
+                self.rel_base |= x.labels;
+            }
+            Op::Halt => return Ok(Some(WhatsUp::Halt)),
+            Op::Ext(..) | Op::Invalid => return Ok(self.vm.apply(op).flatten()),
+        }
+        Ok(None)
+    }
+
+    /// Read an operand, adding the labels of its address.
+    fn get(&mut self, o: Operand<Tainted>, param: Labels) -> Result<Tainted, FaultKind> {
+        let address = self.address_labels(&o, param);
+        let x = self.vm.get(o).ok_or(FaultKind::BadOperand)?;
+        Ok(x.with(address))
+    }
+
+    /// Write an operand, adding the labels of its address and of the branches taken.
+    fn set(&mut self, o: Operand<Tainted>, param: Labels, x: Tainted) -> Result<(), FaultKind> {
+        let labels = self.address_labels(&o, param) | self.control.unwrap_or_default();
+        self.vm.set(o, x.with(labels)).ok_or(FaultKind::BadOperand)
+    }
+
+    fn address_labels(&self, o: &Operand<Tainted>, param: Labels) -> Labels {
+        match o {
+            Operand::Imm(_) => Labels::EMPTY,
+            Operand::Pos(_) => param,
+            Operand::Rel(_) => param | self.rel_base,
+            Operand::Pop | Operand::Push => self.rel_base,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labelled(values: &[i64]) -> Vec<Tainted> {
+        let labels = (0..).map(Labels::single);
+        values
+            .iter()
+            .zip(labels)
+            .map(|(&x, l)| Tainted::new(x, l))
+            .collect()
+    }
+
+    fn labels(labels: &[u32]) -> Labels {
+        labels
+            .iter()
+            .map(|&l| Labels::single(l))
+            .fold(Labels::EMPTY, ops::BitOr::bitor)
+    }
+
+    #[test]
+    fn arithmetic_and_comparisons() {
+        // output a + b, a * 3 and a < b
+        let prog = [
+            3, 30, 3, 31, 1, 30, 31, 32, 4, 32, 1002, 30, 3, 32, 4, 32, 7, 30, 31, 32, 4, 32, 99,
+        ];
+        let output = Tracker::new(&prog).map(labelled(&[5, 7]));
+        let values: Vec<_> = output.iter().map(|x| x.value).collect();
+        assert_eq!(values, vec![12, 15, 1]);
+        let output: Vec<_> = output.iter().map(|x| x.labels).collect();
+        assert_eq!(output, vec![labels(&[0, 1]), labels(&[0]), labels(&[0, 1])]);
+    }
+
+    #[test]
+    fn memory_and_addresses() {
+        // store the input, then output the cell at the address given by the second input
+        let prog = [3, 20, 3, 9, 1001, 20, 0, 21, 4, 0, 99];
+        let mut tracker = Tracker::new(&prog);
+        let output = tracker.map(labelled(&[42, 21]));
+        assert_eq!(output[0].value, 42);
+        assert_eq!(output[0].labels, labels(&[0, 1]));
+        let cells = tracker.tainted_cells();
+        assert_eq!(cells[&20], labels(&[0]));
+        assert_eq!(cells[&21], labels(&[0]));
+        assert_eq!(cells[&9], labels(&[1]));
+
+        // move the relative base by the input, then output a constant from there
+        let prog = [3, 3, 109, 0, 204, 0, 99, 0, 0, 0, 0, 77];
+        let output = Tracker::new(&prog).map(labelled(&[11]));
+        assert_eq!(output[0].value, 77);
+        assert_eq!(output[0].labels, labels(&[0]));
+    }
+
+    #[test]
+    fn control_dependencies() {
+        // output 1 if the input is nonzero, 0 otherwise
+        let prog = [3, 20, 1005, 20, 8, 104, 0, 99, 104, 1, 99];
+        let output = Tracker::new(&prog).map(labelled(&[3]));
+        assert_eq!(output[0].value, 1);
+        assert!(output[0].labels.is_empty());
+
+        let mut tracker = Tracker::new(&prog).with_control_dependencies();
+        let output = tracker.map(labelled(&[3]));
+        assert_eq!(output[0].labels, labels(&[0]));
+    }
+
+    #[test]
+    fn unlabelled_inputs() {
+        let prog = [3, 9, 1001, 9, 1, 9, 4, 9, 99, 0];
+        let output = Tracker::new(&prog).map(vec![Tainted::from(1)]);
+        assert_eq!(output[0].value, 2);
+        assert!(output[0].labels.is_empty());
+        assert_eq!(labels(&[2, 5]).to_string(), "{2, 5}");
+    }
+
+    #[test]
+    fn operand_outside_memory() {
+        let mut tracker = Tracker::new(&[109, -10, 204, 0, 99]);
+        let fault = Fault {
+            pc: 2,
+            kind: FaultKind::BadOperand,
+        };
+        assert_eq!(tracker.run(), Some(WhatsUp::Fault(fault)));
+    }
+}
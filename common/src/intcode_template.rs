@@ -0,0 +1,178 @@
+//! Run a program up to some point once, then start many VMs from there.
+//!
+//! Instantiating a template copies the captured memory into a VM instead of loading and
+//! running the program again, and `reset` reuses a VM's memory instead of allocating it.
+
+use crate::intcode2::{Computable, ComputerImpl, Hooks, WhatsUp};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A snapshot of a VM.
+#[derive(Clone)]
+pub struct VmTemplate<T: Computable, H: Hooks + Clone = ()> {
+    vm: ComputerImpl<T, H>,
+    output: Vec<T>,
+}
+
+impl<T: Computable, H: Hooks + Clone> VmTemplate<T, H> {
+    /// Capture `vm` as it is.
+    pub fn new(vm: ComputerImpl<T, H>) -> Self {
+        VmTemplate { vm, output: vec![] }
+    }
+
+    /// Run `vm` until it asks for input that was not queued; `None` if it stops otherwise.
+    pub fn at_input(mut vm: ComputerImpl<T, H>) -> Option<Self> {
+        let mut output = vec![];
+        loop {
+            match vm.run(None)? {
+                WhatsUp::NeedInput => return Some(VmTemplate { vm, output }),
+                WhatsUp::Output(x) => output.push(x),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Run `vm` until it is about to execute the instruction at `pc`; `None` if it stops
+    /// before getting there.
+    pub fn at_pc(mut vm: ComputerImpl<T, H>, pc: usize) -> Option<Self> {
+        let budget = vm.budget();
+        let mut output = vec![];
+        while vm.pc != pc {
+            vm.set_budget(Some(1));
+            match vm.run(None)? {
+                WhatsUp::BudgetExhausted => {}
+                WhatsUp::Output(x) => output.push(x),
+                _ => return None,
+            }
+        }
+        vm.set_budget(budget);
+        Some(VmTemplate { vm, output })
+    }
+
+    /// The output produced before the VM was captured.
+    pub fn output(&self) -> &[T] {
+        &self.output
+    }
+
+    pub fn instantiate(&self) -> ComputerImpl<T, H> {
+        self.vm.clone()
+    }
+
+    /// Put `vm` back into the captured state.
+    pub fn reset(&self, vm: &mut ComputerImpl<T, H>) {
+        vm.clone_from(&self.vm);
+    }
+
+    /// Run a new instance on `input`; the output does not include `output()`.
+    pub fn map(&self, input: impl Iterator<Item = T>) -> Option<Vec<T>> {
+        self.instantiate().map(input)
+    }
+
+    /// `map` every input, spread across all available cores. Inputs the program does not halt
+    /// on map to `None`.
+    pub fn map_batch(&self, inputs: &[Vec<T>]) -> Vec<Option<Vec<T>>>
+    where
+        T: Send + Sync,
+        H: Send,
+    {
+        let n_threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(inputs.len());
+        let next = &AtomicUsize::new(0);
+
+        let mut results: Vec<_> = inputs.iter().map(|_| None).collect();
+        std::thread::scope(|s| {
+            let workers: Vec<_> = (0..n_threads)
+                .map(|_| {
+                    let template = self.vm.clone();
+                    s.spawn(move || {
+                        let mut vm = template.clone();
+                        let mut done = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            if i >= inputs.len() {
+                                break done;
+                            }
+                            vm.clone_from(&template);
+                            done.push((i, vm.try_map(inputs[i].iter().cloned())));
+                        }
+                    })
+                })
+                .collect();
+            for w in workers {
+                for (i, r) in w.join().unwrap() {
+                    results[i] = r;
+                }
+            }
+        });
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode2::Computer;
+
+    /// Print a greeting, then output the sum of each pair of inputs.
+    const ADDER: [i64; 17] = [
+        104, 42, 3, 15, 3, 16, 1, 15, 16, 15, 4, 15, 1105, 1, 2, 0, 0,
+    ];
+
+    #[test]
+    fn capture_at_input() {
+        let template = VmTemplate::at_input(Computer::new(&ADDER)).unwrap();
+        assert_eq!(template.output(), &[42]);
+
+        let mut vm = template.instantiate();
+        assert_eq!(vm.pc, 2);
+        vm.push_input(vec![1, 2, 3, 4]);
+        assert_eq!(vm.run(None), Some(WhatsUp::Output(3)));
+        assert_eq!(vm.run(None), Some(WhatsUp::Output(7)));
+        assert_eq!(vm.run(None), Some(WhatsUp::NeedInput));
+
+        template.reset(&mut vm);
+        assert_eq!((vm.pc, vm.memory()[15]), (2, 0));
+        assert_eq!(vm.run(Some(5)), Some(WhatsUp::NeedInput));
+        assert_eq!(vm.run(Some(6)), Some(WhatsUp::Output(11)));
+
+        assert!(VmTemplate::at_input(Computer::new(&[104, 1, 99])).is_none());
+    }
+
+    #[test]
+    fn capture_at_pc() {
+        let mut vm = Computer::new(&ADDER);
+        vm.push_input(vec![1, 2]);
+        let template = VmTemplate::at_pc(vm, 10).unwrap();
+        assert_eq!(template.output(), &[42]);
+        assert_eq!(template.instantiate().run(None), Some(WhatsUp::Output(3)));
+        assert_eq!(template.instantiate().budget(), None);
+
+        assert!(VmTemplate::at_pc(Computer::new(&ADDER), 10).is_none());
+    }
+
+    #[test]
+    fn batch() {
+        let template = VmTemplate::new(Computer::new(&[
+            3, 11, 3, 12, 2, 11, 12, 11, 4, 11, 99, 0, 0,
+        ]));
+        let inputs: Vec<_> = (0..100).map(|i| vec![i, i + 1]).collect();
+        let expected: Vec<_> = inputs.iter().map(|v| Some(vec![v[0] * v[1]])).collect();
+        assert_eq!(template.map_batch(&inputs), expected);
+        assert_eq!(template.map(vec![6, 7].into_iter()), Some(vec![42]));
+        assert!(template.map_batch(&[]).is_empty());
+
+        // multiply pairs until the product is zero; running out of input or budget only
+        // fails the input it happens to
+        let template = VmTemplate::new(
+            Computer::new(&[3, 20, 3, 21, 2, 20, 21, 20, 4, 20, 1005, 20, 0, 99]).with_budget(20),
+        );
+        let inputs = [
+            vec![2, 3],
+            vec![4],
+            vec![5, 0],
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+        ];
+        let expected = [None, None, Some(vec![0]), None];
+        assert_eq!(template.map_batch(&inputs), expected);
+    }
+}
@@ -1,9 +1,24 @@
+pub mod affine;
 pub mod backtracking;
+pub mod cycle;
 pub mod expression;
+pub mod grid;
+pub mod input;
 pub mod intcode;
 pub mod intcode2;
+pub mod intcode_abstract;
+pub mod intcode_aot;
 pub mod intcode_decompile;
 pub mod intcode_jit;
+pub mod intcode_runtime;
+pub mod intcode_taint;
+pub mod intcode_template;
+pub mod number_theory;
+pub mod ocr;
+pub mod regression;
+pub mod render;
+pub mod runner;
+pub mod search;
 
 use num::{Num, Signed};
 use std::ops::BitAnd;
@@ -37,6 +52,40 @@ where
     a
 }
 
+/// Extended Euclidean algorithm.
+///
+/// Returns `(g, x, y)` such that `g = gcd(a, b)` and `a * x + b * y == g`.
+pub fn extended_gcd<T>(a: T, b: T) -> (T, T, T)
+where
+    T: Copy + Num + Signed,
+{
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (T::one(), T::zero());
+    let (mut y0, mut y1) = (T::zero(), T::one());
+
+    while !r1.is_zero() {
+        let q = r0 / r1;
+
+        let r = r0 - q * r1;
+        r0 = r1;
+        r1 = r;
+
+        let x = x0 - q * x1;
+        x0 = x1;
+        x1 = x;
+
+        let y = y0 - q * y1;
+        y0 = y1;
+        y1 = y;
+    }
+
+    if r0.is_negative() {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
 pub fn lcm<T>(a: T, b: T) -> T
 where
     T: Copy + Num + Signed + PartialOrd,
@@ -44,6 +93,78 @@ where
     (a * b).abs() / gcd(a, b)
 }
 
+/// Overflow-free `(a * b) % m` for `0 <= a, b < m`.
+pub trait MulMod: Sized {
+    fn mul_mod(self, rhs: Self, m: Self) -> Self;
+}
+
+macro_rules! impl_widening_mul_mod {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl MulMod for $t {
+                fn mul_mod(self, rhs: Self, m: Self) -> Self {
+                    ((self as $wide * rhs as $wide) % m as $wide) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_widening_mul_mod!(
+    i8 => i16, i16 => i32, i32 => i64, i64 => i128, isize => i128,
+    u8 => u16, u16 => u32, u32 => u64, u64 => u128, usize => u128
+);
+
+macro_rules! impl_doubling_mul_mod {
+    ($($t:ty),*) => {
+        $(
+            impl MulMod for $t {
+                fn mul_mod(self, rhs: Self, m: Self) -> Self {
+                    let small = (1 as $t) << 63;
+                    if self < small && rhs < small {
+                        return (self * rhs) % m;
+                    }
+
+                    // no wider type available: double and add
+                    let (mut a, mut b, mut r) = (self, rhs, 0);
+                    while b > 0 {
+                        if b & 1 == 1 {
+                            r = add_mod(r, a, m);
+                        }
+                        a = add_mod(a, a, m);
+                        b >>= 1;
+                    }
+                    r
+                }
+            }
+        )*
+    };
+}
+
+impl_doubling_mul_mod!(i128, u128);
+
+/// Overflow-free `(a + b) % m` for `0 <= a, b < m`.
+fn add_mod<T: Copy + Num + PartialOrd>(a: T, b: T, m: T) -> T {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// Overflow-free `(a - b) % m` for `0 <= a, b < m`.
+fn sub_mod<T: Copy + Num + PartialOrd>(a: T, b: T, m: T) -> T {
+    if a >= b {
+        a - b
+    } else {
+        a + (m - b)
+    }
+}
+
+/// An integer modulo `modulo`.
+///
+/// Arithmetic never overflows as long as the modulus fits into the type, so
+/// `ModularValue<i64>` and `ModularValue<u64>` work with moduli up to `2^63`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct ModularValue<T> {
     value: T,
@@ -52,7 +173,7 @@ pub struct ModularValue<T> {
 
 impl<T> ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     pub fn new(value: T, modulo: T) -> Self {
         ModularValue {
@@ -63,23 +184,37 @@ where
 
     fn modulo(x: T, m: T) -> T {
         let y = x % m;
-        if y.is_negative() {
-            (y + m) % m
+        if y < T::zero() {
+            y + m
         } else {
             y
         }
     }
 }
 
+impl<T> ModularValue<T>
+where
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod + Signed,
+{
+    pub fn inv(self) -> Option<Self> {
+        let (g, x, _) = extended_gcd(self.value, self.modulo);
+        if g.is_one() {
+            Some(ModularValue::new(x, self.modulo))
+        } else {
+            None
+        }
+    }
+}
+
 impl<T> ModularValue<T>
 where
     T: std::fmt::Debug
         + Copy
         + Num
-        + Signed
+        + PartialOrd
+        + MulMod
         + BitAnd<Output = T>
-        + std::ops::Shr<Output = T>
-        + PartialOrd,
+        + std::ops::Shr<Output = T>,
 {
     pub fn pow(self, rhs: T) -> Self {
         let mut a = self;
@@ -92,7 +227,7 @@ where
             },
             self.modulo,
         );
-        while b.is_positive() {
+        while b > T::zero() {
             if (b & T::one()) == T::one() {
                 r = r * a;
             }
@@ -101,15 +236,6 @@ where
         }
         r
     }
-
-    pub fn inv(self) -> Option<Self> {
-        let g = gcd(self.value, self.modulo);
-        if g.is_one() {
-            Some(self.pow(self.modulo - T::one() - T::one()))
-        } else {
-            None
-        }
-    }
 }
 
 impl<T: std::fmt::Display> std::fmt::Display for ModularValue<T> {
@@ -120,13 +246,13 @@ impl<T: std::fmt::Display> std::fmt::Display for ModularValue<T> {
 
 impl<T> std::ops::Add for ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
         debug_assert_eq!(self.modulo, rhs.modulo);
         ModularValue {
-            value: Self::modulo(self.value + rhs.value, self.modulo),
+            value: add_mod(self.value, rhs.value, self.modulo),
             modulo: self.modulo,
         }
     }
@@ -134,13 +260,13 @@ where
 
 impl<T> std::ops::Sub for ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
         debug_assert_eq!(self.modulo, rhs.modulo);
         ModularValue {
-            value: Self::modulo(self.value - rhs.value, self.modulo),
+            value: sub_mod(self.value, rhs.value, self.modulo),
             modulo: self.modulo,
         }
     }
@@ -148,13 +274,13 @@ where
 
 impl<T> std::ops::Mul for ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self {
         debug_assert_eq!(self.modulo, rhs.modulo);
         ModularValue {
-            value: Self::modulo(self.value * rhs.value, self.modulo),
+            value: self.value.mul_mod(rhs.value, self.modulo),
             modulo: self.modulo,
         }
     }
@@ -162,13 +288,7 @@ where
 
 impl<T> std::ops::Div for ModularValue<T>
 where
-    T: std::fmt::Debug
-        + Copy
-        + Num
-        + Signed
-        + BitAnd<Output = T>
-        + std::ops::Shr<Output = T>
-        + PartialOrd,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod + Signed,
 {
     type Output = Option<Self>;
     fn div(self, rhs: Self) -> Option<Self> {
@@ -178,40 +298,31 @@ where
 
 impl<T> std::ops::Add<T> for ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     type Output = ModularValue<T>;
     fn add(self, rhs: T) -> Self {
-        ModularValue {
-            value: ModularValue::modulo(self.value + rhs, self.modulo),
-            modulo: self.modulo,
-        }
+        self + ModularValue::new(rhs, self.modulo)
     }
 }
 
 impl<T> std::ops::Sub<T> for ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     type Output = ModularValue<T>;
     fn sub(self, rhs: T) -> Self {
-        ModularValue {
-            value: ModularValue::modulo(self.value - rhs, self.modulo),
-            modulo: self.modulo,
-        }
+        self - ModularValue::new(rhs, self.modulo)
     }
 }
 
 impl<T> std::ops::Mul<T> for ModularValue<T>
 where
-    T: std::fmt::Debug + Copy + Num + Signed,
+    T: std::fmt::Debug + Copy + Num + PartialOrd + MulMod,
 {
     type Output = ModularValue<T>;
     fn mul(self, rhs: T) -> Self {
-        ModularValue {
-            value: ModularValue::modulo(self.value * rhs, self.modulo),
-            modulo: self.modulo,
-        }
+        self * ModularValue::new(rhs, self.modulo)
     }
 }
 
@@ -220,3 +331,101 @@ impl<T: PartialEq> PartialEq<T> for ModularValue<T> {
         self.value == *other
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for a in -60i64..60 {
+            for b in -60..60 {
+                let (g, x, y) = extended_gcd(a, b);
+                assert_eq!(g, gcd(a, b), "gcd({}, {})", a, b);
+                assert_eq!(a * x + b * y, g, "bezout({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_prime_moduli() {
+        for &m in &[2, 3, 5, 7, 11, 13, 31, 97, 101, 1009] {
+            check_inverses(m);
+        }
+    }
+
+    #[test]
+    fn inverse_composite_moduli() {
+        for &m in &[
+            1, 4, 6, 8, 9, 10, 12, 15, 16, 21, 25, 36, 100, 119, 1001, 1024,
+        ] {
+            check_inverses(m);
+        }
+    }
+
+    #[test]
+    fn inverse_large_modulus() {
+        let m = 119315717514047i64;
+        for &v in &[1, 2, 3, 2020, 101741582076661, m - 1] {
+            let x = ModularValue::new(v as i128, m as i128);
+            assert_eq!(x * x.inv().unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn division_by_non_invertible_value() {
+        let a = ModularValue::new(3, 12);
+        assert_eq!(a / ModularValue::new(4, 12), None);
+        assert_eq!(a / ModularValue::new(5, 12), Some(ModularValue::new(3, 12)));
+    }
+
+    #[test]
+    fn mul_mod_large_moduli() {
+        let m = (1i64 << 62) + 135;
+        let (a, b) = (m - 1, m - 2);
+        let expected = ((a as i128 * b as i128) % m as i128) as i64;
+        assert_eq!(a.mul_mod(b, m), expected);
+        assert_eq!((a as u64).mul_mod(b as u64, m as u64), expected as u64);
+        assert_eq!((a as i128).mul_mod(b as i128, m as i128), expected as i128);
+
+        let m = (1u128 << 100) + 277;
+        let (a, b) = (m - 1, m - 1);
+        assert_eq!(a.mul_mod(b, m), 1);
+    }
+
+    #[test]
+    fn i64_matches_i128_near_2_pow_63() {
+        let m = 0x7fff_ffff_ffff_ffe7;
+        let values = [0, 1, 2, 3, 1 << 32, 1 << 62, m / 2, m - 2, m - 1];
+        for &a in &values {
+            for &b in &values {
+                let x = ModularValue::new(a, m);
+                let y = ModularValue::new(b, m);
+                let wide = |v: i64| ModularValue::new(v as i128, m as i128);
+                let as_wide = |v: ModularValue<i64>| wide(v.value);
+                assert_eq!(as_wide(x + y), wide(a) + wide(b));
+                assert_eq!(as_wide(x - y), wide(a) - wide(b));
+                assert_eq!(as_wide(x * y), wide(a) * wide(b));
+                assert_eq!(as_wide(x.pow(b)), wide(a).pow(b as i128));
+            }
+        }
+    }
+
+    #[test]
+    fn unsigned_values() {
+        let m = (1u64 << 63) - 25;
+        let x = ModularValue::new(m - 1, m);
+        assert_eq!(x + x, m - 2);
+        assert_eq!(x * x, 1);
+        assert_eq!(ModularValue::new(0, m) - x, 1);
+        assert_eq!(x.pow(m - 1), 1);
+    }
+
+    fn check_inverses(m: i64) {
+        for v in -m..2 * m {
+            let brute_force = (0..m).find(|x| (v * x).rem_euclid(m) == 1 % m);
+            let inv = ModularValue::new(v, m).inv().map(|x| x.value);
+            assert_eq!(inv, brute_force, "inverse of {} mod {}", v, m);
+        }
+    }
+}
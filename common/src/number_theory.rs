@@ -0,0 +1,266 @@
+//! Congruences and discrete logarithms.
+//!
+//! All functions work on any signed primitive integer. Intermediate products are reduced
+//! with `MulMod`, so they do not overflow as long as the moduli (and, for `crt`, the
+//! combined modulus) fit into the type.
+
+use crate::{gcd, ModularValue, MulMod};
+use num::integer::Roots;
+use num::{Num, Signed};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Chinese remainder theorem.
+///
+/// Solve the system `x ≡ r (mod m)` for all `(r, m)` in `congruences`. The moduli need not
+/// be pairwise coprime. Returns `(x, l)` with `0 <= x < l` where `l` is the least common
+/// multiple of all moduli, so that every solution is of the form `x + k * l`.
+///
+/// Returns `None` if the congruences contradict each other or a modulus is not positive.
+/// An empty system is solved by every integer: `Some((0, 1))`.
+pub fn crt<T>(congruences: &[(T, T)]) -> Option<(T, T)>
+where
+    T: Debug + Copy + Num + Signed + PartialOrd + MulMod,
+{
+    let mut x = T::zero();
+    let mut l = T::one();
+    for &(r, m) in congruences {
+        if m <= T::zero() {
+            return None;
+        }
+        let r = ModularValue::new(r, m).value;
+
+        // x + l * k ≡ r (mod m)
+        let g = gcd(l, m);
+        let d = ModularValue::new(r - x, m).value;
+        if !(d % g).is_zero() {
+            return None;
+        }
+        let m_g = m / g;
+        let k = ModularValue::new(l / g, m_g).inv()? * (d / g);
+
+        x = x + l * k.value;
+        l = l * m_g;
+    }
+    Some((x, l))
+}
+
+/// Solve `a * x ≡ b (mod m)`.
+///
+/// Yields every solution in `0..m` in increasing order. There are either none (if
+/// `gcd(a, m)` does not divide `b`, or `m` is not positive) or exactly `gcd(a, m)` of them.
+pub fn solve_linear_congruence<T>(a: T, b: T, m: T) -> impl Iterator<Item = T>
+where
+    T: Debug + Copy + Num + Signed + PartialOrd + MulMod,
+{
+    let mut first = None;
+    let mut step = T::one();
+    if m > T::zero() {
+        let a = ModularValue::new(a, m).value;
+        let b = ModularValue::new(b, m).value;
+
+        let g = gcd(a, m);
+        step = m / g;
+        if (b % g).is_zero() {
+            first = ModularValue::new(a / g, step)
+                .inv()
+                .map(|inv| (inv * (b / g)).value);
+        }
+    }
+
+    std::iter::successors(
+        first,
+        move |&x| {
+            if x < m - step {
+                Some(x + step)
+            } else {
+                None
+            }
+        },
+    )
+}
+
+/// Discrete logarithm by baby-step giant-step.
+///
+/// Returns the smallest `x >= 0` with `base^x ≡ target (mod m)`, or `None` if there is no
+/// such `x` or `m` is not positive. `base` and `m` need not be coprime. Takes `O(sqrt(m))`
+/// time and memory.
+pub fn discrete_log<T>(base: T, target: T, m: T) -> Option<T>
+where
+    T: Debug + Copy + Num + Signed + PartialOrd + MulMod + Roots + Hash + Eq,
+{
+    if m <= T::zero() {
+        return None;
+    }
+    let mut m = m;
+    let a = ModularValue::new(base, m).value;
+    let mut b = ModularValue::new(target, m).value;
+
+    // Divide out common factors of base and modulus until they are coprime:
+    // base^x ≡ target (mod m)  =>  k * base^(x - offset) ≡ b (mod m)
+    let mut k = T::one() % m;
+    let mut offset = T::zero();
+    loop {
+        let g = gcd(a, m);
+        if g.is_one() {
+            break;
+        }
+        if b == k {
+            return Some(offset);
+        }
+        if !(b % g).is_zero() {
+            return None;
+        }
+        b = b / g;
+        m = m / g;
+        offset = offset + T::one();
+        k = (k % m).mul_mod((a / g) % m, m);
+    }
+    let a = a % m;
+
+    let n = m.sqrt() + T::one();
+
+    // baby steps: b * a^q for q in 0..=n; later q win so the answer is minimal
+    let mut baby_steps = HashMap::new();
+    let mut cur = b;
+    let mut q = T::zero();
+    while q <= n {
+        baby_steps.insert(cur, q);
+        cur = cur.mul_mod(a, m);
+        q = q + T::one();
+    }
+
+    // giant steps: k * a^(n * p) for p in 1..=n
+    let mut an = T::one() % m;
+    let mut i = T::zero();
+    while i < n {
+        an = an.mul_mod(a, m);
+        i = i + T::one();
+    }
+
+    let mut cur = k;
+    let mut p = T::one();
+    while p <= n {
+        cur = cur.mul_mod(an, m);
+        if let Some(&q) = baby_steps.get(&cur) {
+            return Some(n * p - q + offset);
+        }
+        p = p + T::one();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pow_mod(b: i64, e: i64, m: i64) -> i64 {
+        ModularValue::new(b, m).pow(e).value
+    }
+
+    #[test]
+    fn crt_coprime() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(0, 7), (12, 13), (55, 59)]), Some((350, 5369)));
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn crt_non_coprime() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 6), (5, 10), (-10, 15)]), Some((5, 30)));
+    }
+
+    #[test]
+    fn crt_invalid_modulus() {
+        assert_eq!(crt(&[(1, 3), (0, 0)]), None);
+        assert_eq!(crt(&[(1, -3)]), None);
+    }
+
+    #[test]
+    fn crt_large_moduli() {
+        let m1 = 1_000_000_007i64;
+        let m2 = 998_244_353i64;
+        let x = 123_456_789_012_345_678i64 % (m1 * m2);
+        assert_eq!(crt(&[(x % m1, m1), (x % m2, m2)]), Some((x, m1 * m2)));
+    }
+
+    #[test]
+    fn crt_brute_force() {
+        for m1 in 1..13 {
+            for m2 in 1..13 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let l = m1 * m2 / gcd(m1, m2);
+                        let expected = (0..l).find(|x| x % m1 == r1 && x % m2 == r2);
+                        assert_eq!(crt(&[(r1, m1), (r2, m2)]), expected.map(|x| (x, l)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn linear_congruence() {
+        let solutions: Vec<_> = solve_linear_congruence(6, 4, 10).collect();
+        assert_eq!(solutions, vec![4, 9]);
+
+        assert_eq!(solve_linear_congruence(6, 3, 10).count(), 0);
+        assert_eq!(solve_linear_congruence(1, 0, 0).count(), 0);
+        assert_eq!(solve_linear_congruence(1, 0, -5).count(), 0);
+        assert_eq!(
+            solve_linear_congruence(0, 0, 3).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            solve_linear_congruence(-3, 1, 7).collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn linear_congruence_brute_force() {
+        for m in 1..30i64 {
+            for a in 0..m {
+                for b in 0..m {
+                    let expected: Vec<_> = (0..m).filter(|x| (a * x) % m == b).collect();
+                    let actual: Vec<_> = solve_linear_congruence(a, b, m).collect();
+                    assert_eq!(actual, expected, "{} * x = {} (mod {})", a, b, m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn discrete_log_prime_modulus() {
+        let m = 20_201_227;
+        assert_eq!(discrete_log(7, 5_764_801, m), Some(8));
+        assert_eq!(discrete_log(7, 17_807_724, m), Some(11));
+        assert_eq!(discrete_log(7, 1, 0), None);
+
+        let m = 1_000_000_007;
+        let x = discrete_log(5, 123_456_789, m).unwrap();
+        assert_eq!(pow_mod(5, x, m), 123_456_789);
+    }
+
+    #[test]
+    fn discrete_log_brute_force() {
+        for m in 1..40i64 {
+            for a in 0..m {
+                for b in 0..m {
+                    let expected = (0..2 * m).find(|&x| pow_mod(a, x, m) == b % m);
+                    assert_eq!(
+                        discrete_log(a, b, m),
+                        expected,
+                        "{}^x = {} (mod {})",
+                        a,
+                        b,
+                        m
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Read the block letters that some puzzles draw.
+//!
+//! Letters are 4 pixels wide and 6 pixels high, separated by one empty column.
+
+use crate::grid::{BoundingBox, Grid, Pos};
+
+const HEIGHT: usize = 6;
+const WIDTH: usize = 4;
+const STRIDE: i64 = WIDTH as i64 + 1;
+
+const FONT: [(char, &str); 16] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OcrError {
+    /// The lit pixels span this many rows instead of 6.
+    Height(usize),
+
+    /// Bitmaps of the glyphs that are not in the font, as rows of `#` and `.`.
+    UnknownGlyphs(Vec<String>),
+}
+
+impl std::fmt::Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OcrError::Height(h) => write!(f, "text is {} pixels high, expected {}", h, HEIGHT),
+            OcrError::UnknownGlyphs(glyphs) => {
+                writeln!(f, "{} unknown glyph(s):", glyphs.len())?;
+                for g in glyphs {
+                    writeln!(f, "{}", g)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Read the text drawn by the `true` pixels.
+pub fn recognize(pixels: &Grid<bool>) -> Result<String, OcrError> {
+    recognize_points(pixels.iter().filter(|(_, &lit)| lit).map(|(p, _)| p))
+}
+
+/// Read the text drawn by the lit pixels at `points`.
+///
+/// The text may be anywhere; it starts at the leftmost lit pixel.
+pub fn recognize_points(points: impl IntoIterator<Item = Pos>) -> Result<String, OcrError> {
+    let points: Vec<_> = points.into_iter().collect();
+    let bb = match BoundingBox::from_points(points.iter().copied()) {
+        Some(bb) => bb,
+        None => return Ok(String::new()),
+    };
+    if bb.height() != HEIGHT {
+        return Err(OcrError::Height(bb.height()));
+    }
+
+    let n_glyphs = (bb.width() + 1 + WIDTH) / STRIDE as usize;
+    let mut bitmaps = vec![vec![b'.'; WIDTH * HEIGHT]; n_glyphs];
+    for p in points {
+        let x = (p.x - bb.min.x) as usize;
+        let y = (p.y - bb.min.y) as usize;
+        let (glyph, col) = (x / STRIDE as usize, x % STRIDE as usize);
+        if col < WIDTH {
+            bitmaps[glyph][y * WIDTH + col] = b'#';
+        }
+    }
+
+    let mut text = String::new();
+    let mut unknown = vec![];
+    for bitmap in bitmaps {
+        let bitmap = String::from_utf8(bitmap).unwrap();
+        match FONT.iter().find(|(_, g)| *g == bitmap) {
+            Some(&(ch, _)) => text.push(ch),
+            None => {
+                text.push('?');
+                let rows: Vec<_> = bitmap
+                    .as_bytes()
+                    .chunks(WIDTH)
+                    .map(|row| String::from_utf8_lossy(row))
+                    .collect();
+                unknown.push(rows.join("\n"));
+            }
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::UnknownGlyphs(unknown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str) -> Grid<bool> {
+        let width = text.len() * STRIDE as usize - 1;
+        let mut grid = Grid::new(width, HEIGHT, false);
+        for (i, ch) in text.chars().enumerate() {
+            let (_, bitmap) = FONT.iter().find(|(c, _)| *c == ch).unwrap();
+            for (j, pixel) in bitmap.bytes().enumerate() {
+                let p = Pos::new((i * STRIDE as usize + j % WIDTH) as i64, (j / WIDTH) as i64);
+                grid[p] = pixel == b'#';
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn font_is_consistent() {
+        for (ch, bitmap) in &FONT {
+            assert_eq!(bitmap.len(), WIDTH * HEIGHT, "{}", ch);
+            assert_eq!(
+                FONT.iter().filter(|(_, b)| b == bitmap).count(),
+                1,
+                "{}",
+                ch
+            );
+        }
+    }
+
+    #[test]
+    fn all_letters() {
+        let letters: String = FONT.iter().map(|(ch, _)| ch).collect();
+        assert_eq!(recognize(&render(&letters)), Ok(letters));
+    }
+
+    #[test]
+    fn text_with_margin() {
+        let text = Grid::parse(
+            "
+..............................................
+.####.####..##..#..#.#..#.####..##...##.......
+.#....#....#..#.#.#..#..#.#....#..#.#..#......
+.###..###..#....##...#..#.###..#....#.........
+.#....#....#....#.#..#..#.#....#.##.#.........
+.#....#....#..#.#.#..#..#.#....#..#.#..#......
+.####.#.....##..#..#..##..####..###..##.......
+.............................................."
+                .trim(),
+            |ch| ch == '#',
+        )
+        .unwrap();
+        assert_eq!(recognize(&text), Ok("EFCKUEGC".to_string()));
+    }
+
+    #[test]
+    fn points_anywhere() {
+        let points = render("LJ")
+            .iter()
+            .filter(|(_, &lit)| lit)
+            .map(|(p, _)| p + Pos::new(-20, 7))
+            .collect::<Vec<_>>();
+        assert_eq!(recognize_points(points), Ok("LJ".to_string()));
+        assert_eq!(recognize_points(vec![]), Ok(String::new()));
+    }
+
+    #[test]
+    fn errors() {
+        let mut grid = render("HAL");
+        assert_eq!(recognize(&grid), Ok("HAL".to_string()));
+
+        grid[Pos::new(5, 0)] = true;
+        assert_eq!(
+            recognize(&grid),
+            Err(OcrError::UnknownGlyphs(vec![
+                "###.\n#..#\n#..#\n####\n#..#\n#..#".to_string()
+            ]))
+        );
+
+        let mut grid = Grid::new(4, 8, false);
+        grid[Pos::new(0, 0)] = true;
+        grid[Pos::new(0, 7)] = true;
+        assert_eq!(recognize(&grid), Err(OcrError::Height(8)));
+    }
+}
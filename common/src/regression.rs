@@ -0,0 +1,176 @@
+//! Compare answers against recorded known-good answers.
+//!
+//! Answers are recorded one per line as `day part answer`, optionally followed by `slow`.
+//! Empty lines and lines starting with `#` are ignored.
+
+use crate::input::InputError;
+use crate::runner::{Day, Input};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Expected {
+    pub answer: String,
+
+    /// Takes too long to check on every `cargo test`.
+    pub slow: bool,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<(u32, u32), Expected>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, InputError> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |token: &str, error: &str| InputError::Parse {
+                line: i + 1,
+                token: token.to_string(),
+                error: error.to_string(),
+            };
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let slow = match fields.get(3) {
+                None => false,
+                Some(&"slow") if fields.len() == 4 => true,
+                Some(_) => return Err(error(line, "expected `day part answer [slow]`")),
+            };
+            if fields.len() < 3 {
+                return Err(error(line, "expected `day part answer [slow]`"));
+            }
+            let day = fields[0]
+                .parse()
+                .map_err(|_| error(fields[0], "invalid day"))?;
+            let part = fields[1]
+                .parse()
+                .map_err(|_| error(fields[1], "invalid part"))?;
+            let expected = Expected {
+                answer: fields[2].to_string(),
+                slow,
+            };
+            if answers.insert((day, part), expected).is_some() {
+                return Err(error(line, "answer recorded twice"));
+            }
+        }
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&Expected> {
+        self.answers.get(&(day, part))
+    }
+
+    /// The days that have at least one recorded answer.
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        let mut days: Vec<_> = self.answers.keys().map(|&(day, _)| day).collect();
+        days.dedup();
+        days.into_iter()
+    }
+
+    /// Compare an answer against the recorded one; `None` if it matches or nothing is recorded.
+    pub fn compare(&self, day: u32, part: u32, actual: &str) -> Option<Mismatch> {
+        let expected = self.get(day, part)?;
+        if expected.answer == actual {
+            None
+        } else {
+            Some(Mismatch {
+                day,
+                part,
+                expected: expected.answer.clone(),
+                actual: actual.to_string(),
+            })
+        }
+    }
+
+    /// Solve every part of `day` that has a recorded answer and collect the mismatches.
+    pub fn check(&self, day: &Day, input: &Input, include_slow: bool) -> Vec<Mismatch> {
+        (1..=2)
+            .filter(|&part| match self.get(day.number, part) {
+                Some(expected) => include_slow || !expected.slow,
+                None => false,
+            })
+            .filter_map(|part| {
+                let (actual, _) = day.solve(part, input)?;
+                self.compare(day.number, part, actual.as_str())
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mismatch {
+    pub day: u32,
+    pub part: u32,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "day {} part {}:", self.day, self.part)?;
+        writeln!(f, "- {}", self.expected)?;
+        for line in self.actual.lines() {
+            writeln!(f, "+ {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{Answer, DefaultInput};
+
+    const ANSWERS: &str = "
+# day part answer
+1 1 6
+1 2 ABC slow
+3 1 x
+";
+
+    fn sum(input: &Input) -> Answer {
+        input.program().iter().sum::<i64>().into()
+    }
+
+    fn letters(_: &Input) -> Answer {
+        "ABD".into()
+    }
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get(1, 1).unwrap().answer, "6");
+        assert!(answers.get(1, 2).unwrap().slow);
+        assert_eq!(answers.get(2, 1), None);
+        assert_eq!(answers.days().collect::<Vec<_>>(), vec![1, 3]);
+
+        assert!(Answers::parse("1 1").is_err());
+        assert!(Answers::parse("1 1 6 fast").is_err());
+        assert!(Answers::parse("x 1 6").is_err());
+        assert!(Answers::parse("1 1 6\n1 1 7").is_err());
+    }
+
+    #[test]
+    fn check() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let day = Day {
+            number: 1,
+            input: DefaultInput::Intcode(&[1, 2, 3]),
+            part1: sum,
+            part2: Some(letters),
+        };
+        let input = Input::Intcode(vec![1, 2, 3]);
+        assert_eq!(answers.check(&day, &input, false), vec![]);
+
+        let mismatches = answers.check(&day, &input, true);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].to_string(), "day 1 part 2:\n- ABC\n+ ABD\n");
+
+        let input = Input::Intcode(vec![1, 2]);
+        assert_eq!(answers.check(&day, &input, false)[0].actual, "3");
+    }
+}
@@ -0,0 +1,308 @@
+//! Render grids as text, ANSI-coloured text, PBM/PPM images or SVG.
+//!
+//! Every renderer covers the bounding box of a `Canvas` and asks a mapping function how to
+//! draw each position, passing the cell at that position if there is one.
+
+use crate::grid::{BoundingBox, Grid, Pos, SparseGrid};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Something with cells at integer positions.
+pub trait Canvas {
+    type Cell;
+
+    /// The area to render, or `None` if there is nothing to render.
+    fn bounding_box(&self) -> Option<BoundingBox>;
+
+    fn cell(&self, p: Pos) -> Option<&Self::Cell>;
+}
+
+impl<T> Canvas for Grid<T> {
+    type Cell = T;
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        Grid::bounding_box(self)
+    }
+
+    fn cell(&self, p: Pos) -> Option<&T> {
+        self.get(p)
+    }
+}
+
+impl<T> Canvas for SparseGrid<T> {
+    type Cell = T;
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        SparseGrid::bounding_box(self)
+    }
+
+    fn cell(&self, p: Pos) -> Option<&T> {
+        self.get(p)
+    }
+}
+
+impl<T> Canvas for HashMap<Pos, T> {
+    type Cell = T;
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.keys().copied())
+    }
+
+    fn cell(&self, p: Pos) -> Option<&T> {
+        self.get(&p)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// A character in a given colour.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Glyph {
+    pub ch: char,
+    pub color: Rgb,
+}
+
+impl Glyph {
+    pub fn new(ch: char, color: Rgb) -> Self {
+        Glyph { ch, color }
+    }
+}
+
+type Row<'a, T> = Vec<(Pos, Option<&'a T>)>;
+
+/// Rows of the bounding box; empty if there is none.
+fn rows<C: Canvas>(canvas: &C) -> Vec<Row<'_, C::Cell>> {
+    let bb = match canvas.bounding_box() {
+        Some(bb) => bb,
+        None => return vec![],
+    };
+    (bb.min.y..=bb.max.y)
+        .map(|y| {
+            (bb.min.x..=bb.max.x)
+                .map(|x| Pos::new(x, y))
+                .map(|p| (p, canvas.cell(p)))
+                .collect()
+        })
+        .collect()
+}
+
+/// One character per cell, one line per row.
+pub fn to_text<C: Canvas>(
+    canvas: &C,
+    mut glyph: impl FnMut(Pos, Option<&C::Cell>) -> char,
+) -> String {
+    let mut out = String::new();
+    for row in rows(canvas) {
+        out.extend(row.into_iter().map(|(p, cell)| glyph(p, cell)));
+        out.push('\n');
+    }
+    out
+}
+
+/// Like `to_text`, but coloured with 24 bit ANSI escape sequences.
+pub fn to_ansi<C: Canvas>(
+    canvas: &C,
+    mut glyph: impl FnMut(Pos, Option<&C::Cell>) -> Glyph,
+) -> String {
+    let mut out = String::new();
+    for row in rows(canvas) {
+        let mut current = None;
+        for (p, cell) in row {
+            let Glyph { ch, color } = glyph(p, cell);
+            if current != Some(color) {
+                let Rgb(r, g, b) = color;
+                out += &format!("\x1b[38;2;{};{};{}m", r, g, b);
+                current = Some(color);
+            }
+            out.push(ch);
+        }
+        out += "\x1b[0m\n";
+    }
+    out
+}
+
+/// Binary PBM image; `true` pixels are black.
+pub fn write_pbm<C: Canvas>(
+    mut out: impl Write,
+    canvas: &C,
+    mut black: impl FnMut(Pos, Option<&C::Cell>) -> bool,
+) -> io::Result<()> {
+    let rows = rows(canvas);
+    let width = rows.first().map(Vec::len).unwrap_or(0);
+    write!(out, "P4\n{} {}\n", width, rows.len())?;
+    for row in rows {
+        let bits: Vec<_> = row.into_iter().map(|(p, cell)| black(p, cell)).collect();
+        let bytes: Vec<u8> = bits
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &bit)| bit)
+                    .map(|(i, _)| 0x80 >> i)
+                    .sum()
+            })
+            .collect();
+        out.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Binary PPM image with one pixel per cell.
+pub fn write_ppm<C: Canvas>(
+    mut out: impl Write,
+    canvas: &C,
+    mut color: impl FnMut(Pos, Option<&C::Cell>) -> Rgb,
+) -> io::Result<()> {
+    let rows = rows(canvas);
+    let width = rows.first().map(Vec::len).unwrap_or(0);
+    write!(out, "P6\n{} {}\n255\n", width, rows.len())?;
+    for row in rows {
+        for (p, cell) in row {
+            let Rgb(r, g, b) = color(p, cell);
+            out.write_all(&[r, g, b])?;
+        }
+    }
+    Ok(())
+}
+
+/// SVG image with a `cell_size` square for every cell that `color` does not map to `None`.
+pub fn write_svg<C: Canvas>(
+    mut out: impl Write,
+    canvas: &C,
+    cell_size: u32,
+    mut color: impl FnMut(Pos, Option<&C::Cell>) -> Option<Rgb>,
+) -> io::Result<()> {
+    let rows = rows(canvas);
+    let width = rows.first().map(Vec::len).unwrap_or(0) as u32 * cell_size;
+    let height = rows.len() as u32 * cell_size;
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" shape-rendering="crispEdges">"#,
+        width, height
+    )?;
+    for (y, row) in rows.into_iter().enumerate() {
+        for (x, (p, cell)) in row.into_iter().enumerate() {
+            if let Some(Rgb(r, g, b)) = color(p, cell) {
+                writeln!(
+                    out,
+                    r##"<rect x="{}" y="{}" width="{s}" height="{s}" fill="#{:02x}{:02x}{:02x}"/>"##,
+                    x as u32 * cell_size,
+                    y as u32 * cell_size,
+                    r,
+                    g,
+                    b,
+                    s = cell_size
+                )?;
+            }
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letter_l() -> SparseGrid<bool> {
+        vec![
+            (Pos::new(-1, 3), true),
+            (Pos::new(-1, 4), true),
+            (Pos::new(-1, 5), true),
+            (Pos::new(0, 5), true),
+            (Pos::new(1, 5), false),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn glyph(_: Pos, cell: Option<&bool>) -> char {
+        match cell {
+            Some(true) => '█',
+            Some(false) => '▒',
+            None => '░',
+        }
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(to_text(&letter_l(), glyph), "█░░\n█░░\n██▒\n");
+
+        let grid = Grid::parse_chars("ab\ncd").unwrap();
+        assert_eq!(to_text(&grid, |_, ch| *ch.unwrap()), "ab\ncd\n");
+        assert_eq!(to_text(&SparseGrid::<u8>::new(), |_, _| 'x'), "");
+    }
+
+    #[test]
+    fn text_marks_positions() {
+        let text = to_text(&letter_l(), |p, cell| {
+            if p == Pos::new(0, 4) {
+                '@'
+            } else {
+                glyph(p, cell)
+            }
+        });
+        assert_eq!(text, "█░░\n█@░\n██▒\n");
+    }
+
+    #[test]
+    fn ansi() {
+        let red = Rgb(255, 0, 0);
+        let ansi = to_ansi(&letter_l(), |p, cell| {
+            let color = if cell.is_some() { red } else { Rgb::WHITE };
+            Glyph::new(glyph(p, cell), color)
+        });
+        let lines: Vec<_> = ansi.lines().collect();
+        assert_eq!(
+            lines[0],
+            "\x1b[38;2;255;0;0m█\x1b[38;2;255;255;255m░░\x1b[0m"
+        );
+        assert_eq!(lines[2], "\x1b[38;2;255;0;0m██▒\x1b[0m");
+    }
+
+    #[test]
+    fn pbm() {
+        let mut grid = Grid::new(10, 2, false);
+        grid[Pos::new(0, 0)] = true;
+        grid[Pos::new(9, 1)] = true;
+
+        let mut out = vec![];
+        write_pbm(&mut out, &grid, |_, cell| *cell.unwrap()).unwrap();
+        assert_eq!(out, b"P4\n10 2\n\x80\x00\x00\x40");
+    }
+
+    #[test]
+    fn ppm() {
+        let mut out = vec![];
+        write_ppm(&mut out, &letter_l(), |_, cell| match cell {
+            Some(true) => Rgb::BLACK,
+            _ => Rgb(1, 2, 3),
+        })
+        .unwrap();
+        assert!(out.starts_with(b"P6\n3 3\n255\n"));
+        assert_eq!(out.len(), 11 + 3 * 3 * 3);
+        assert_eq!(&out[11..17], &[0, 0, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn svg() {
+        let mut out = vec![];
+        write_svg(&mut out, &letter_l(), 10, |_, cell| match cell {
+            Some(true) => Some(Rgb(255, 128, 0)),
+            _ => None,
+        })
+        .unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r#"width="30" height="30""#));
+        assert!(svg.contains(r##"<rect x="10" y="20" width="10" height="10" fill="#ff8000"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,219 @@
+//! The interface every day exposes to the `aoc` runner.
+
+use crate::input::{self, InputError, Source};
+use std::time::{Duration, Instant};
+
+/// A loaded puzzle input.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Input {
+    Text(String),
+    Intcode(Vec<i64>),
+    Numbers(Vec<i64>),
+}
+
+impl Input {
+    /// Panics if this is an intcode program.
+    pub fn text(&self) -> &str {
+        match self {
+            Input::Text(text) => text,
+            Input::Intcode(_) => panic!("expected a text input, got an intcode program"),
+            Input::Numbers(_) => panic!("expected a text input, got numbers"),
+        }
+    }
+
+    /// Panics if this is not an intcode program.
+    pub fn program(&self) -> &[i64] {
+        match self {
+            Input::Intcode(program) => program,
+            Input::Text(_) => panic!("expected an intcode program, got a text input"),
+            Input::Numbers(_) => panic!("expected an intcode program, got numbers"),
+        }
+    }
+
+    /// Panics if this is not a list of numbers.
+    pub fn numbers(&self) -> &[i64] {
+        match self {
+            Input::Numbers(numbers) => numbers,
+            Input::Text(_) => panic!("expected numbers, got a text input"),
+            Input::Intcode(_) => panic!("expected numbers, got an intcode program"),
+        }
+    }
+}
+
+/// What a day reads if no input is given on the command line.
+#[derive(Debug, Copy, Clone)]
+pub enum DefaultInput {
+    /// The day's file in the data directory.
+    File,
+    /// The day's file, one number per line.
+    Numbers,
+    Text(&'static str),
+    Intcode(&'static [i64]),
+}
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Answer(String);
+
+impl Answer {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+macro_rules! impl_answer_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(x: $t) -> Self {
+                    Answer(x.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from!(i32, i64, u32, u64, usize, String, &str);
+
+pub type Part = fn(&Input) -> Answer;
+
+pub struct Day {
+    pub number: u32,
+    pub input: DefaultInput,
+    pub part1: Part,
+
+    /// Day 25 has no second part.
+    pub part2: Option<Part>,
+}
+
+impl Day {
+    pub fn load(&self, source: &Source) -> Result<Input, InputError> {
+        match (self.input, source) {
+            (DefaultInput::Text(text), Source::Default) => Ok(Input::Text(text.to_string())),
+            (DefaultInput::Intcode(program), Source::Default) => {
+                Ok(Input::Intcode(program.to_vec()))
+            }
+            (DefaultInput::Intcode(_), source) => {
+                Ok(Input::Intcode(input::intcode(&source.read(self.number)?)?))
+            }
+            (DefaultInput::Numbers, source) => {
+                Ok(Input::Numbers(input::lines(&source.read(self.number)?)?))
+            }
+            (_, source) => Ok(Input::Text(source.read(self.number)?)),
+        }
+    }
+
+    /// The part with number `part` (1 or 2), if the day has it.
+    pub fn part(&self, part: u32) -> Option<Part> {
+        match part {
+            1 => Some(self.part1),
+            2 => self.part2,
+            _ => None,
+        }
+    }
+
+    pub fn solve(&self, part: u32, input: &Input) -> Option<(Answer, Duration)> {
+        let f = self.part(part)?;
+        let start = Instant::now();
+        let answer = f(input);
+        Some((answer, start.elapsed()))
+    }
+
+    /// Print the answers to all parts.
+    pub fn report(&self, input: &Input) {
+        for part in 1..=2 {
+            if let Some((answer, _)) = self.solve(part, input) {
+                println!("Part {}: {}", part, answer);
+            }
+        }
+    }
+}
+
+/// The `main` of a day's binary: solve the input given on the command line.
+pub fn main(day: &Day) -> Result<(), InputError> {
+    let input = day.load(&Source::from_args())?;
+    day.report(&input);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(input: &Input) -> Answer {
+        input.program().iter().sum::<i64>().into()
+    }
+
+    fn count_lines(input: &Input) -> Answer {
+        input.text().lines().count().into()
+    }
+
+    #[test]
+    fn embedded_inputs() {
+        let day = Day {
+            number: 1,
+            input: DefaultInput::Intcode(&[1, 2, 3]),
+            part1: sum,
+            part2: None,
+        };
+        let input = day.load(&Source::Default).unwrap();
+        assert_eq!(day.solve(1, &input).unwrap().0, Answer::from(6));
+        assert!(day.solve(2, &input).is_none());
+
+        let day = Day {
+            number: 2,
+            input: DefaultInput::Text("a\nb\n"),
+            part1: count_lines,
+            part2: Some(count_lines),
+        };
+        let input = day.load(&Source::Default).unwrap();
+        assert_eq!(day.solve(2, &input).unwrap().0.as_str(), "2");
+    }
+
+    #[test]
+    fn intcode_from_file() {
+        let path = std::env::temp_dir().join(format!("aoc-runner-{}.txt", std::process::id()));
+        std::fs::write(&path, "4,5\n").unwrap();
+        let day = Day {
+            number: 3,
+            input: DefaultInput::Intcode(&[]),
+            part1: sum,
+            part2: None,
+        };
+        let input = day.load(&Source::File(path.clone())).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(input, Input::Intcode(vec![4, 5]));
+        assert_eq!(day.solve(1, &input).unwrap().0.to_string(), "9");
+    }
+
+    #[test]
+    fn numbers_from_file() {
+        let path = std::env::temp_dir().join(format!("aoc-numbers-{}.txt", std::process::id()));
+        let day = Day {
+            number: 4,
+            input: DefaultInput::Numbers,
+            part1: |input| input.numbers().iter().sum::<i64>().into(),
+            part2: None,
+        };
+        std::fs::write(&path, "12\n-3\n").unwrap();
+        let input = day.load(&Source::File(path.clone())).unwrap();
+        assert_eq!(input, Input::Numbers(vec![12, -3]));
+
+        std::fs::write(&path, "12\nx\n").unwrap();
+        let err = day.load(&Source::File(path.clone())).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(err, InputError::Parse { line: 2, .. }), "{}", err);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a text input")]
+    fn wrong_input_kind() {
+        let _ = count_lines(&Input::Intcode(vec![]));
+    }
+}
@@ -0,0 +1,439 @@
+//! Shortest path searches over implicit graphs: breadth first, Dijkstra and A*.
+//!
+//! The graph is given by a neighbour function that generates the nodes adjacent to a node on
+//! demand. Nodes are identified by a key, which is the node itself unless `Search::with_key`
+//! says otherwise; this allows searching over nodes that cannot be hashed or are expensive to
+//! compare, such as intcode machines identified by their position. Each key is visited only
+//! once unless `revisit` turns this off.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A goal node and how to get there.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Found<N, K, C> {
+    pub node: N,
+    pub cost: C,
+
+    /// The keys of the nodes from a start node to `node`, both included.
+    pub path: Vec<K>,
+}
+
+pub struct Search<N, F> {
+    starts: Vec<N>,
+    key: F,
+    revisit: bool,
+}
+
+impl<N: Hash + Eq + Clone> Search<N, fn(&N) -> N> {
+    /// Search from all `starts` at once, identifying nodes by themselves.
+    pub fn new(starts: impl IntoIterator<Item = N>) -> Self {
+        Search::with_key(starts, N::clone as fn(&N) -> N)
+    }
+}
+
+impl<N, K, F> Search<N, F>
+where
+    F: Fn(&N) -> K,
+    K: Hash + Eq + Clone,
+{
+    /// Search from all `starts` at once, identifying nodes by `key`.
+    pub fn with_key(starts: impl IntoIterator<Item = N>, key: F) -> Self {
+        Search {
+            starts: starts.into_iter().collect(),
+            key,
+            revisit: false,
+        }
+    }
+
+    /// Do not remember visited nodes, but explore every path.
+    ///
+    /// The search then only terminates if it finds a goal or the graph is a finite tree.
+    pub fn revisit(mut self) -> Self {
+        self.revisit = true;
+        self
+    }
+
+    /// Find the goal with the fewest steps.
+    ///
+    /// Nodes are tested when they are generated, so the search stops as soon as a goal is
+    /// seen rather than when it would be expanded.
+    pub fn bfs<I>(
+        self,
+        mut neighbours: impl FnMut(&N) -> I,
+        mut goal: impl FnMut(&N) -> bool,
+    ) -> Option<Found<N, K, usize>>
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let mut bfs = Bfs {
+            links: Links::default(),
+            visited: HashSet::new(),
+            queue: VecDeque::new(),
+            revisit: self.revisit,
+        };
+        for start in self.starts {
+            if let Some(found) = bfs.visit(start, 0, None, &self.key, &mut goal) {
+                return Some(found);
+            }
+        }
+        while let Some((node, cost, id)) = bfs.queue.pop_front() {
+            for next in neighbours(&node) {
+                if let Some(found) = bfs.visit(next, cost + 1, Some(id), &self.key, &mut goal) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Find the cheapest goal; `neighbours` returns the adjacent nodes with the cost of
+    /// getting there.
+    pub fn dijkstra<C, I>(
+        self,
+        neighbours: impl FnMut(&N) -> I,
+        goal: impl FnMut(&N) -> bool,
+    ) -> Option<Found<N, K, C>>
+    where
+        C: Copy + Ord + Default + Add<Output = C>,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        self.astar(neighbours, |_| C::default(), goal)
+    }
+
+    /// Like `dijkstra`, but nodes whose estimated cost to a goal is lower are expanded first.
+    ///
+    /// The result is the cheapest goal if the `heuristic` never overestimates and never
+    /// drops by more than the cost of a step.
+    pub fn astar<C, I>(
+        self,
+        mut neighbours: impl FnMut(&N) -> I,
+        mut heuristic: impl FnMut(&N) -> C,
+        mut goal: impl FnMut(&N) -> bool,
+    ) -> Option<Found<N, K, C>>
+    where
+        C: Copy + Ord + Default + Add<Output = C>,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        let mut astar = AStar {
+            links: Links::default(),
+            best: HashMap::new(),
+            pending: vec![],
+            queue: BinaryHeap::new(),
+            revisit: self.revisit,
+        };
+        for start in self.starts {
+            astar.push(start, C::default(), None, &self.key, &mut heuristic);
+        }
+
+        while let Some(Reverse((_, id))) = astar.queue.pop() {
+            let (node, cost) = astar.pending[id].take().unwrap();
+            if !self.revisit && astar.best[&astar.links.keys[id]] < cost {
+                // a cheaper way was found after this one was queued
+                continue;
+            }
+            if goal(&node) {
+                return Some(Found {
+                    node,
+                    cost,
+                    path: astar.links.path(id),
+                });
+            }
+            for (next, step) in neighbours(&node) {
+                astar.push(next, cost + step, Some(id), &self.key, &mut heuristic);
+            }
+        }
+        None
+    }
+
+    /// All nodes reachable from the start nodes with their number of steps, nearest first.
+    pub fn reachable<G, I>(self, neighbours: G) -> Reachable<N, K, F, G>
+    where
+        G: FnMut(&N) -> I,
+        I: IntoIterator<Item = N>,
+    {
+        let mut reachable = Reachable {
+            queue: VecDeque::new(),
+            visited: HashSet::new(),
+            search_key: self.key,
+            revisit: self.revisit,
+            neighbours,
+        };
+        for start in self.starts {
+            reachable.push(start, 0);
+        }
+        reachable
+    }
+}
+
+struct Bfs<N, K> {
+    links: Links<K>,
+    visited: HashSet<K>,
+    queue: VecDeque<(N, usize, usize)>,
+    revisit: bool,
+}
+
+impl<N, K: Hash + Eq + Clone> Bfs<N, K> {
+    /// Queue a newly generated node, or return it if it is a goal.
+    fn visit(
+        &mut self,
+        node: N,
+        cost: usize,
+        parent: Option<usize>,
+        key: impl Fn(&N) -> K,
+        goal: impl FnOnce(&N) -> bool,
+    ) -> Option<Found<N, K, usize>> {
+        let key = key(&node);
+        if !self.revisit && !self.visited.insert(key.clone()) {
+            return None;
+        }
+        let id = self.links.add(key, parent);
+        if goal(&node) {
+            return Some(Found {
+                node,
+                cost,
+                path: self.links.path(id),
+            });
+        }
+        self.queue.push_back((node, cost, id));
+        None
+    }
+}
+
+struct AStar<N, K, C> {
+    links: Links<K>,
+    best: HashMap<K, C>,
+    /// The nodes in the queue and their costs, by link id.
+    pending: Vec<Option<(N, C)>>,
+    queue: BinaryHeap<Reverse<(C, usize)>>,
+    revisit: bool,
+}
+
+impl<N, K, C> AStar<N, K, C>
+where
+    K: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C>,
+{
+    /// Queue a node unless it is known to be reachable at a lower cost.
+    fn push(
+        &mut self,
+        node: N,
+        cost: C,
+        parent: Option<usize>,
+        key: impl Fn(&N) -> K,
+        heuristic: impl FnOnce(&N) -> C,
+    ) {
+        let key = key(&node);
+        if !self.revisit {
+            match self.best.get(&key) {
+                Some(&known) if known <= cost => return,
+                _ => self.best.insert(key.clone(), cost),
+            };
+        }
+        let id = self.links.add(key, parent);
+        self.queue.push(Reverse((cost + heuristic(&node), id)));
+        self.pending.push(Some((node, cost)));
+    }
+}
+
+/// Breadth first traversal; see `Search::reachable`.
+pub struct Reachable<N, K, F, G> {
+    queue: VecDeque<(N, usize)>,
+    visited: HashSet<K>,
+    search_key: F,
+    revisit: bool,
+    neighbours: G,
+}
+
+impl<N, K, F, G> Reachable<N, K, F, G>
+where
+    F: Fn(&N) -> K,
+    K: Hash + Eq,
+{
+    fn push(&mut self, node: N, steps: usize) {
+        if self.revisit || self.visited.insert((self.search_key)(&node)) {
+            self.queue.push_back((node, steps));
+        }
+    }
+}
+
+impl<N, K, F, G, I> Iterator for Reachable<N, K, F, G>
+where
+    F: Fn(&N) -> K,
+    K: Hash + Eq,
+    G: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Item = (N, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, steps) = self.queue.pop_front()?;
+        for next in (self.neighbours)(&node) {
+            self.push(next, steps + 1);
+        }
+        Some((node, steps))
+    }
+}
+
+/// The search tree, for reconstructing paths.
+struct Links<K> {
+    keys: Vec<K>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<K> Default for Links<K> {
+    fn default() -> Self {
+        Links {
+            keys: vec![],
+            parents: vec![],
+        }
+    }
+}
+
+impl<K: Clone> Links<K> {
+    fn add(&mut self, key: K, parent: Option<usize>) -> usize {
+        self.keys.push(key);
+        self.parents.push(parent);
+        self.keys.len() - 1
+    }
+
+    fn path(&self, mut id: usize) -> Vec<K> {
+        let mut path = vec![self.keys[id].clone()];
+        while let Some(parent) = self.parents[id] {
+            path.push(self.keys[parent].clone());
+            id = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    const MAZE: &str = "
+#########
+#S..#...#
+#.#.#.#.#
+#.#...#G#
+#########";
+
+    fn maze() -> (Grid<char>, Pos, Pos) {
+        let grid = Grid::parse_chars(MAZE.trim()).unwrap();
+        let find = |c| grid.iter().find(|(_, &x)| x == c).unwrap().0;
+        let (start, goal) = (find('S'), find('G'));
+        (grid, start, goal)
+    }
+
+    fn moves<'a>(grid: &'a Grid<char>) -> impl Fn(&Pos) -> Vec<Pos> + 'a {
+        move |&pos| {
+            pos.neighbors()
+                .filter(|p| matches!(grid.get(*p), Some(&c) if c != '#'))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn breadth_first() {
+        let (grid, start, goal) = maze();
+        let found = Search::new(vec![start])
+            .bfs(moves(&grid), |&p| p == goal)
+            .unwrap();
+        assert_eq!(found.cost, 12);
+        assert_eq!(found.path.len(), 13);
+        assert_eq!(found.path[0], start);
+        assert_eq!(found.node, goal);
+        for step in found.path.windows(2) {
+            assert_eq!(step[0].manhattan(step[1]), 1);
+        }
+
+        assert_eq!(
+            Search::new(vec![start]).bfs(moves(&grid), |&p| p == Pos::new(0, 0)),
+            None
+        );
+        let found = Search::new(vec![start]).bfs(moves(&grid), |&p| p == start);
+        assert_eq!(found.unwrap().path, vec![start]);
+    }
+
+    #[test]
+    fn several_starts() {
+        let (grid, start, goal) = maze();
+        let found = Search::new(vec![start, Pos::new(5, 1)])
+            .bfs(moves(&grid), |&p| p == goal)
+            .unwrap();
+        assert_eq!(found.cost, 4);
+        assert_eq!(found.path[0], Pos::new(5, 1));
+
+        let found = Search::new(vec![start, Pos::new(5, 1)])
+            .dijkstra(
+                |p| moves(&grid)(p).into_iter().map(|p| (p, 1)),
+                |&p| p == goal,
+            )
+            .unwrap();
+        assert_eq!(found.cost, 4);
+    }
+
+    #[test]
+    fn weighted() {
+        // going straight to 3 is expensive
+        let edges = |&n: &u32| match n {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 2)],
+            2 => vec![(3, 3), (0, 1)],
+            _ => vec![],
+        };
+        let found = Search::new(vec![0]).dijkstra(edges, |&n| n == 3).unwrap();
+        assert_eq!((found.cost, found.path), (6, vec![0, 1, 2, 3]));
+        assert_eq!(Search::new(vec![0]).dijkstra(edges, |&n| n == 4), None);
+    }
+
+    #[test]
+    fn astar_agrees_with_bfs() {
+        let (grid, start, goal) = maze();
+        let found = Search::new(vec![start])
+            .astar(
+                |p| moves(&grid)(p).into_iter().map(|p| (p, 1)),
+                |&p| p.manhattan(goal),
+                |&p| p == goal,
+            )
+            .unwrap();
+        assert_eq!(found.cost, 12);
+        assert_eq!(found.path.len(), 13);
+    }
+
+    #[test]
+    fn reachable() {
+        let (grid, start, _) = maze();
+        let distances: HashMap<Pos, usize> =
+            Search::new(vec![start]).reachable(moves(&grid)).collect();
+        assert_eq!(distances.len(), 15);
+        assert_eq!(distances[&Pos::new(7, 1)], 10);
+        assert_eq!(distances.values().max(), Some(&12));
+    }
+
+    #[test]
+    fn keys_and_revisits() {
+        // nodes carry a step counter, but are identified by their position
+        let (grid, start, goal) = maze();
+        let moves = moves(&grid);
+        let next = |&(p, n): &(Pos, u32)| moves(&p).into_iter().map(move |p| (p, n + 1));
+        let found = Search::with_key(vec![(start, 0)], |&(p, _)| p)
+            .bfs(next, |&(p, _)| p == goal)
+            .unwrap();
+        assert_eq!(found.node, (goal, 12));
+        assert_eq!(found.path.last(), Some(&goal));
+
+        // without a visited set, walks may turn back
+        let paths = Search::new(vec![start])
+            .revisit()
+            .reachable(&moves)
+            .take_while(|&(_, steps)| steps <= 3)
+            .filter(|&(_, steps)| steps == 3)
+            .count();
+        assert_eq!(paths, 7);
+    }
+}
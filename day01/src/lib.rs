@@ -0,0 +1,36 @@
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+pub const DAY: Day = Day {
+    number: 1,
+    input: DefaultInput::Numbers,
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    input
+        .numbers()
+        .iter()
+        .map(|x| x / 3 - 2)
+        .sum::<i64>()
+        .into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    input
+        .numbers()
+        .iter()
+        .copied()
+        .map(calc_fuel)
+        .sum::<i64>()
+        .into()
+}
+
+fn calc_fuel(mass: i64) -> i64 {
+    let fuel_for_mass = mass / 3 - 2;
+    if fuel_for_mass > 0 {
+        fuel_for_mass + calc_fuel(fuel_for_mass)
+    } else {
+        0
+    }
+}
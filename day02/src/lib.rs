@@ -0,0 +1,56 @@
+use common::intcode::Computer;
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+pub const DAY: Day = Day {
+    number: 2,
+    input: DefaultInput::Intcode(&INPUT),
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let mut c = Computer::new(input.program());
+    c.sr[1] = 12;
+    c.sr[2] = 2;
+    while c.step().unwrap() {}
+    c.sr[0].into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let target = 19690720;
+
+    let (noun, verb) = (0..100)
+        .flat_map(|noun| (0..100).map(move |verb| (noun, verb)))
+        .find(|&(noun, verb)| part2_compute(input.program(), noun, verb).unwrap_or(-1) == target)
+        .expect("No solution found");
+
+    (100 * noun + verb).into()
+}
+
+fn part2_compute(program: &[i64], noun: i64, verb: i64) -> Option<i64> {
+    let mut c = Computer::new(program);
+    c.sr[1] = noun;
+    c.sr[2] = verb;
+    while c.step()? {}
+    Some(c.sr[0])
+}
+
+/// Print how each memory cell is used by the program.
+pub fn extra(program: &[i64]) {
+    let mut c = Computer::new(program);
+    c.sr[1] = 67;
+    c.sr[2] = 18;
+    let mut cls = vec![Default::default(); program.len()];
+    while c.classify_step(&mut cls).unwrap() {}
+    for (i, ((inp, c), mem)) in program.iter().zip(cls).zip(&c.sr).enumerate() {
+        println!("{:4} {} {:4} -> {}", i, c, inp, mem);
+    }
+}
+
+const INPUT: [i64; 129] = [
+    1, 0, 0, 3, 1, 1, 2, 3, 1, 3, 4, 3, 1, 5, 0, 3, 2, 13, 1, 19, 1, 6, 19, 23, 2, 23, 6, 27, 1, 5,
+    27, 31, 1, 10, 31, 35, 2, 6, 35, 39, 1, 39, 13, 43, 1, 43, 9, 47, 2, 47, 10, 51, 1, 5, 51, 55,
+    1, 55, 10, 59, 2, 59, 6, 63, 2, 6, 63, 67, 1, 5, 67, 71, 2, 9, 71, 75, 1, 75, 6, 79, 1, 6, 79,
+    83, 2, 83, 9, 87, 2, 87, 13, 91, 1, 10, 91, 95, 1, 95, 13, 99, 2, 13, 99, 103, 1, 103, 10, 107,
+    2, 107, 10, 111, 1, 111, 9, 115, 1, 115, 2, 119, 1, 9, 119, 0, 99, 2, 0, 14, 0,
+];
@@ -0,0 +1,69 @@
+use common::grid::{Direction, Pos};
+use common::runner::{Answer, Day, DefaultInput, Input};
+use std::collections::{BinaryHeap, HashMap};
+
+pub const DAY: Day = Day {
+    number: 3,
+    input: DefaultInput::File,
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let (mut manhattan, _) = crossings(input.text());
+    (-manhattan.pop().unwrap()).into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let (_, mut steps) = crossings(input.text());
+    (-steps.pop().unwrap()).into()
+}
+
+/// Negated Manhattan and step distances of all crossings, nearest first.
+fn crossings(input: &str) -> (BinaryHeap<i64>, BinaryHeap<i64>) {
+    let mut wires = input.lines().map(|w| {
+        w.split(',')
+            .map(|dn| (direction(&dn[0..1]), dn[1..].parse().unwrap()))
+    });
+
+    let mut w1_map = HashMap::new();
+
+    let mut w1_pos = Pos::default();
+    let mut steps: i64 = 0;
+    for (d, n) in wires.next().unwrap() {
+        for _ in 0..n {
+            w1_pos += d;
+            steps += 1;
+            w1_map.insert(w1_pos, steps);
+        }
+    }
+
+    let mut crossings_manhdist = BinaryHeap::new();
+    let mut crossings_stepdist = BinaryHeap::new();
+
+    let mut w2_pos = Pos::default();
+    let mut steps: i64 = 0;
+    for (d, n) in wires.next().unwrap() {
+        for _ in 0..n {
+            w2_pos += d;
+            steps += 1;
+            if let Some(s) = w1_map.get(&w2_pos) {
+                let total_steps = steps + *s;
+                crossings_stepdist.push(-total_steps);
+                crossings_manhdist.push(-w2_pos.manhattan(Pos::default()));
+            }
+        }
+    }
+
+    (crossings_manhdist, crossings_stepdist)
+}
+
+fn direction(d: &str) -> Direction {
+    match d {
+        "R" => Direction::East,
+        "L" => Direction::West,
+        "U" => Direction::North,
+        "D" => Direction::South,
+        _ => panic!("Invalid direction: {}", d),
+    }
+}
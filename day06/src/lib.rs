@@ -0,0 +1,132 @@
+use common::runner::{Answer, Day, DefaultInput, Input};
+use common::search::Search;
+use std::collections::{HashMap, HashSet};
+
+pub const DAY: Day = Day {
+    number: 6,
+    input: DefaultInput::File,
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    count_orbits("COM", &load(input.text())).into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    find_orbital_distance("YOU", "SAN", &load(input.text())).into()
+}
+
+fn load(input: &str) -> HashMap<&str, SpaceObject> {
+    let mut bodies: HashMap<&str, SpaceObject> = HashMap::new();
+
+    for (a, b) in input.lines().map(|line| {
+        let mut l = line.split(')');
+        let center = l.next().unwrap();
+        (center, l.next().unwrap())
+    }) {
+        if !bodies.contains_key(a) {
+            bodies.insert(a, SpaceObject::new(""));
+        }
+
+        if !bodies.contains_key(b) {
+            bodies.insert(b, SpaceObject::new(""));
+        }
+
+        bodies.get_mut(a).unwrap().orbited_by.insert(b);
+        bodies.get_mut(b).unwrap().orbits = a;
+    }
+
+    bodies
+}
+
+fn count_orbits(root: &str, bodies: &HashMap<&str, SpaceObject>) -> usize {
+    let obj = &bodies[root];
+    obj.orbited_by
+        .iter()
+        .map(|&o| count_orbits(o, bodies))
+        .sum::<usize>()
+        + count_children(root, bodies)
+}
+
+fn count_children(root: &str, bodies: &HashMap<&str, SpaceObject>) -> usize {
+    bodies[root]
+        .orbited_by
+        .iter()
+        .map(|&o| 1 + count_children(o, bodies))
+        .sum::<usize>()
+}
+
+/// The number of transfers between the objects `a` and `b` orbit.
+fn find_orbital_distance(a: &str, b: &str, bodies: &HashMap<&str, SpaceObject>) -> usize {
+    let target = bodies[b].orbits;
+    Search::new(vec![bodies[a].orbits])
+        .bfs(
+            |&body| {
+                let obj = &bodies[body];
+                let center = Some(obj.orbits).filter(|center| !center.is_empty());
+                obj.orbited_by.iter().copied().chain(center)
+            },
+            |&body| body == target,
+        )
+        .expect("the objects are not connected")
+        .cost
+}
+
+struct SpaceObject<'a> {
+    orbits: &'a str,
+    orbited_by: HashSet<&'a str>,
+}
+
+impl<'a> SpaceObject<'a> {
+    pub fn new(orbits: &'a str) -> Self {
+        SpaceObject {
+            orbits,
+            orbited_by: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example1() {
+        let input = "COM)B
+B)C
+C)D
+D)E
+E)F
+B)G
+G)H
+D)I
+E)J
+J)K
+K)L";
+        let bodies = load(input);
+        assert_eq!(count_orbits("K", &bodies), 1);
+        assert_eq!(count_orbits("J", &bodies), 3);
+        assert_eq!(count_orbits("E", &bodies), 7);
+        assert_eq!(count_orbits("COM", &bodies), 42);
+    }
+    #[test]
+
+    fn example2() {
+        let input = "COM)B
+B)C
+C)D
+D)E
+E)F
+B)G
+G)H
+D)I
+E)J
+J)K
+K)L
+K)YOU
+I)SAN";
+        let bodies = load(input);
+        assert_eq!(find_orbital_distance("YOU", "SAN", &bodies), 4);
+    }
+}
@@ -0,0 +1,74 @@
+use common::grid::Grid;
+use common::ocr::recognize_points;
+use common::render::to_text;
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+pub const DAY: Day = Day {
+    number: 8,
+    input: DefaultInput::File,
+    part1,
+    part2: Some(part2),
+};
+
+fn layers(input: &Input) -> Vec<u8> {
+    let data: Vec<_> = input.text().bytes().filter(u8::is_ascii_digit).collect();
+
+    let depth = data.len() / (WIDTH * HEIGHT);
+    assert_eq!(data.len(), depth * WIDTH * HEIGHT);
+    data
+}
+
+pub fn part1(input: &Input) -> Answer {
+    let mut layer_counts = vec![];
+    for layer in layers(input).chunks(WIDTH * HEIGHT) {
+        let mut counts = [0; 10];
+        for digit in layer {
+            counts[(digit - b'0') as usize] += 1;
+        }
+        layer_counts.push(counts);
+    }
+
+    layer_counts
+        .iter()
+        //.inspect(|x|println!("{:?}", x))
+        .min()
+        .map(|c| c[1] * c[2])
+        .unwrap()
+        .into()
+}
+
+/// The decoded message, or the image if it cannot be read.
+pub fn part2(input: &Input) -> Answer {
+    let data = layers(input);
+    let mut image = vec![b'8'; WIDTH * HEIGHT];
+    for layer in data.chunks(WIDTH * HEIGHT).rev() {
+        for (src, dst) in layer.iter().zip(&mut image) {
+            match *src {
+                b'2' => {}
+                s => *dst = s,
+            }
+        }
+    }
+
+    let image = Grid::from_vec(WIDTH, image);
+    let lit = image
+        .iter()
+        .filter(|(_, &pixel)| pixel == b'1')
+        .map(|(p, _)| p);
+    match recognize_points(lit) {
+        Ok(text) => text.into(),
+        Err(e) => format!(
+            "{}\n{}",
+            e,
+            to_text(&image, |_, pixel| if pixel == Some(&b'1') {
+                '#'
+            } else {
+                ' '
+            })
+        )
+        .into(),
+    }
+}
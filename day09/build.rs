@@ -0,0 +1,12 @@
+use std::env;
+use std::path::PathBuf;
+
+include!("src/input.rs");
+
+fn main() {
+    // compile the BOOST program ahead of time and link it into all targets of this crate
+    let obj = PathBuf::from(env::var("OUT_DIR").unwrap()).join("boost.o");
+    common::intcode_aot::write_object(&INPUT, "boost_run", &obj).unwrap();
+    println!("cargo:rustc-link-arg={}", obj.display());
+    println!("cargo:rerun-if-changed=src/input.rs");
+}
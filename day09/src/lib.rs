@@ -0,0 +1,28 @@
+use common::intcode2::Computer;
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+include!("input.rs");
+
+pub const DAY: Day = Day {
+    number: 9,
+    input: DefaultInput::Intcode(&INPUT),
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    boost(input.program(), 1).into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    boost(input.program(), 2).into()
+}
+
+fn boost(program: &[i64], input: i64) -> String {
+    let output = Computer::new(program).map(std::iter::once(input)).unwrap();
+    output
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,316 @@
+use common::gcd;
+use common::grid::{Grid, Pos};
+use common::runner::{Answer, Day, DefaultInput, Input};
+use std::collections::{HashMap, HashSet};
+
+pub const DAY: Day = Day {
+    number: 10,
+    input: DefaultInput::Text(INPUT),
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let map = get_map(input.text());
+    let (_, n) = find_maxpos(&map);
+    n.into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let map = get_map(input.text());
+    let (pos, _) = find_maxpos(&map);
+    let order = laserify(pos, &map);
+
+    let n200 = order[199].pos;
+    (n200.x * 100 + n200.y).into()
+}
+
+fn get_map(input: &str) -> HashSet<Pos> {
+    let grid = Grid::parse(input, |ch| match ch {
+        '.' => false,
+        '#' => true,
+        _ => panic!("unexpected character: {}", ch),
+    })
+    .unwrap();
+    grid.iter()
+        .filter(|(_, &asteroid)| asteroid)
+        .map(|(p, _)| p)
+        .collect()
+}
+
+fn find_maxpos(map: &HashSet<Pos>) -> (Pos, i64) {
+    let mut maxvis = 0;
+    let mut maxpos = Pos::new(9999, 9999);
+
+    for &pos in map {
+        let n = count_visible(pos, &map);
+        if n > maxvis {
+            maxvis = n;
+            maxpos = pos;
+        }
+    }
+
+    (maxpos, maxvis)
+}
+
+fn count_visible(pos: Pos, map: &HashSet<Pos>) -> i64 {
+    let mut vis = HashMap::new();
+
+    let mut count = 0;
+
+    for p in map {
+        if *p == pos {
+            continue;
+        }
+
+        if !vis.contains_key(p) {
+            trace_line(pos, *p, &mut vis, map);
+        }
+
+        if let Visibility::Visible = vis[p] {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn trace_line(from: Pos, to: Pos, vis: &mut HashMap<Pos, Visibility>, map: &HashSet<Pos>) {
+    use Visibility::*;
+    let mut visible = Visible;
+    vis.insert(from, visible);
+    for p in integer_positions(from, to) {
+        vis.insert(p, visible);
+        if map.contains(&p) {
+            visible = Blocked;
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Visibility {
+    Visible,
+    Blocked,
+}
+
+fn integer_positions(from: Pos, to: Pos) -> impl Iterator<Item = Pos> {
+    let d = to - from;
+    let g = gcd(d.x, d.y);
+    let e = if g == 0 {
+        Pos::default()
+    } else {
+        Pos::new(d.x / g, d.y / g)
+    };
+    (1..=g).map(move |i| Pos::new(from.x + e.x * i, from.y + e.y * i))
+}
+
+#[derive(Debug)]
+struct LaserTarget {
+    pos: Pos,
+    phi: Angle,
+    d2: i64,
+}
+
+impl LaserTarget {
+    fn new(pos: Pos, offset: Pos) -> Self {
+        let Pos { x, y } = pos - offset;
+        LaserTarget {
+            pos,
+            phi: Angle::from_f64(f64::atan2(x as f64, -y as f64) * 180.0 / std::f64::consts::PI),
+            d2: (x * x + y * y),
+        }
+    }
+}
+
+fn laserify(laser_pos: Pos, map: &HashSet<Pos>) -> Vec<LaserTarget> {
+    let mut targets: Vec<LaserTarget> = map
+        .iter()
+        .map(|&p| LaserTarget::new(p, laser_pos))
+        .collect();
+
+    let mut destroyed = vec![];
+
+    let mut laser_phi = Angle(0.0);
+    while !targets.is_empty() {
+        targets.sort_by_key(|t| t.d2);
+        targets.sort_by_key(|t| t.phi - laser_phi);
+
+        laser_phi = targets
+            .iter()
+            .find(|t| t.phi > laser_phi + TOL)
+            .map(|t| t.phi)
+            .unwrap_or(laser_phi + TOL);
+
+        let target = targets.swap_remove(0);
+        destroyed.push(target);
+    }
+
+    destroyed
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Angle(f64);
+
+impl Angle {
+    fn from_f64(f: f64) -> Self {
+        Angle((f + 360.0) % 360.0)
+    }
+}
+
+impl std::ops::Sub for Angle {
+    type Output = Angle;
+    fn sub(self, rhs: Self) -> Self {
+        Angle((self.0 - rhs.0 + 360.0) % 360.0)
+    }
+}
+
+impl std::ops::Add for Angle {
+    type Output = Angle;
+    fn add(self, rhs: Self) -> Self {
+        Angle((self.0 + rhs.0 + 360.0) % 360.0)
+    }
+}
+
+impl PartialOrd for Angle {
+    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(&rhs.0)
+    }
+}
+
+impl Ord for Angle {
+    fn cmp(&self, rhs: &Self) -> std::cmp::Ordering {
+        self.partial_cmp(rhs).unwrap()
+    }
+}
+
+impl Eq for Angle {}
+
+const TOL: Angle = Angle(1e-9);
+
+const INPUT: &str = "..#..###....#####....###........#
+.##.##...#.#.......#......##....#
+#..#..##.#..###...##....#......##
+..####...#..##...####.#.......#.#
+...#.#.....##...#.####.#.###.#..#
+#..#..##.#.#.####.#.###.#.##.....
+#.##...##.....##.#......#.....##.
+.#..##.##.#..#....#...#...#...##.
+.#..#.....###.#..##.###.##.......
+.##...#..#####.#.#......####.....
+..##.#.#.#.###..#...#.#..##.#....
+.....#....#....##.####....#......
+.#..##.#.........#..#......###..#
+#.##....#.#..#.#....#.###...#....
+.##...##..#.#.#...###..#.#.#..###
+.#..##..##...##...#.#.#...#..#.#.
+.#..#..##.##...###.##.#......#...
+...#.....###.....#....#..#....#..
+.#...###..#......#.##.#...#.####.
+....#.##...##.#...#........#.#...
+..#.##....#..#.......##.##.....#.
+.#.#....###.#.#.#.#.#............
+#....####.##....#..###.##.#.#..#.
+......##....#.#.#...#...#..#.....
+...#.#..####.##.#.........###..##
+.......#....#.##.......#.#.###...
+...#..#.#.........#...###......#.
+.#.##.#.#.#.#........#.#.##..#...
+.......#.##.#...........#..#.#...
+.####....##..#..##.#.##.##..##...
+.#.#..###.#..#...#....#.###.#..#.
+............#...#...#.......#.#..
+.........###.#.....#..##..#.##...";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example10_1() {
+        let data = ".#..#
+.....
+#####
+....#
+...##";
+        let map = get_map(&data);
+        assert_eq!(find_maxpos(&map), (Pos::new(3, 4), 8))
+    }
+
+    #[test]
+    fn example10_2() {
+        let data = "......#.#.
+#..#.#....
+..#######.
+.#.#.###..
+.#..#.....
+..#....#.#
+#..#....#.
+.##.#..###
+##...#..#.
+.#....####";
+        let map = get_map(&data);
+        assert_eq!(find_maxpos(&map), (Pos::new(5, 8), 33))
+    }
+
+    #[test]
+    fn example10_large_2() {
+        let data = ".#..##.###...#######
+##.############..##.
+.#.######.########.#
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##";
+
+        let mut map = get_map(&data);
+        let pos = Pos::new(11, 13);
+        map.remove(&pos);
+
+        let order = laserify(pos, &map);
+
+        assert_eq!(order[1 - 1].pos, Pos::new(11, 12));
+        assert_eq!(order[2 - 1].pos, Pos::new(12, 1));
+        assert_eq!(order[3 - 1].pos, Pos::new(12, 2));
+        assert_eq!(order[10 - 1].pos, Pos::new(12, 8));
+        assert_eq!(order[20 - 1].pos, Pos::new(16, 0));
+        assert_eq!(order[50 - 1].pos, Pos::new(16, 9));
+        assert_eq!(order[100 - 1].pos, Pos::new(10, 16));
+        assert_eq!(order[199 - 1].pos, Pos::new(9, 6));
+        assert_eq!(order[200 - 1].pos, Pos::new(8, 2));
+        assert_eq!(order[201 - 1].pos, Pos::new(10, 9));
+        //assert_eq!(order[299-1].pos, Pos::new(11, 1));
+    }
+
+    #[test]
+    fn polar_conversion() {
+        assert_eq!(
+            LaserTarget::new(Pos::new(0, -1), Pos::default()).phi,
+            Angle(0.0)
+        );
+        assert_eq!(
+            LaserTarget::new(Pos::new(1, 0), Pos::default()).phi,
+            Angle(90.0)
+        );
+        assert_eq!(
+            LaserTarget::new(Pos::new(0, 1), Pos::default()).phi,
+            Angle(180.0)
+        );
+        assert_eq!(
+            LaserTarget::new(Pos::new(-1, 0), Pos::default()).phi,
+            Angle(270.0)
+        );
+    }
+}
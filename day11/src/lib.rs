@@ -0,0 +1,765 @@
+use common::grid::{Direction, Pos, SparseGrid};
+use common::intcode2::{Computer, WhatsUp};
+use common::ocr::recognize_points;
+use common::render::to_text;
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+pub const DAY: Day = Day {
+    number: 11,
+    input: DefaultInput::Intcode(&INPUT),
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let mut robot = Robot::new(input.program());
+    robot.run();
+    robot.paint.len().into()
+}
+
+/// The registration identifier, or the painted hull if it cannot be read.
+pub fn part2(input: &Input) -> Answer {
+    let mut robot = Robot::new(input.program());
+    robot.paint.insert(Pos::default(), Color::White);
+    robot.run();
+
+    let white = robot
+        .paint
+        .iter()
+        .filter(|(_, &color)| color == Color::White)
+        .map(|(p, _)| p);
+    match recognize_points(white) {
+        Ok(text) => text.into(),
+        Err(e) => format!(
+            "{}\n{}",
+            e,
+            to_text(&robot.paint, |_, color| match color {
+                Some(Color::White) => '█',
+                Some(Color::Black) => '▒',
+                None => '░',
+            })
+        )
+        .into(),
+    }
+}
+
+struct Robot {
+    brain: Computer,
+    direction: Direction,
+    pos: Pos,
+    paint: SparseGrid<Color>,
+}
+
+impl Robot {
+    pub fn new(program: &[i64]) -> Self {
+        Robot {
+            brain: Computer::new(program),
+            direction: Direction::North,
+            pos: Pos::default(),
+            paint: SparseGrid::new(),
+        }
+    }
+
+    pub fn run(&mut self) {
+        loop {
+            match self.brain.run(Some(self.get_color())).unwrap() {
+                WhatsUp::Halt => break,
+                WhatsUp::NeedInput => unreachable!(),
+                WhatsUp::Output(o) => self.paint(o),
+                WhatsUp::Fault(f) => panic!("{}", f),
+                WhatsUp::BudgetExhausted | WhatsUp::InfiniteLoop => unreachable!(),
+            }
+
+            match self.brain.run(None).unwrap() {
+                WhatsUp::Halt => break,
+                WhatsUp::NeedInput => unreachable!(),
+                WhatsUp::Output(o) => self.turn(o),
+                WhatsUp::Fault(f) => panic!("{}", f),
+                WhatsUp::BudgetExhausted | WhatsUp::InfiniteLoop => unreachable!(),
+            }
+
+            self.walk();
+        }
+    }
+
+    fn get_color(&self) -> i64 {
+        self.paint.get(self.pos).copied().unwrap_or(Color::Black) as i64
+    }
+
+    fn paint(&mut self, value: i64) {
+        self.paint.insert(self.pos, Color::from_i64(value));
+    }
+
+    fn turn(&mut self, input: i64) {
+        self.direction = match input {
+            0 => self.direction.turn_left(),
+            1 => self.direction.turn_right(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn walk(&mut self) {
+        self.pos += self.direction;
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+enum Color {
+    Black,
+    White,
+}
+
+impl Color {
+    fn from_i64(i: i64) -> Self {
+        match i {
+            0 => Color::Black,
+            1 => Color::White,
+            _ => panic!(),
+        }
+    }
+}
+
+const INPUT: [i64; 641] = [
+    3,
+    8,
+    1005,
+    8,
+    319,
+    1106,
+    0,
+    11,
+    0,
+    0,
+    0,
+    104,
+    1,
+    104,
+    0,
+    3,
+    8,
+    102,
+    -1,
+    8,
+    10,
+    1001,
+    10,
+    1,
+    10,
+    4,
+    10,
+    108,
+    1,
+    8,
+    10,
+    4,
+    10,
+    101,
+    0,
+    8,
+    28,
+    2,
+    1105,
+    12,
+    10,
+    1006,
+    0,
+    12,
+    3,
+    8,
+    102,
+    -1,
+    8,
+    10,
+    101,
+    1,
+    10,
+    10,
+    4,
+    10,
+    1008,
+    8,
+    0,
+    10,
+    4,
+    10,
+    102,
+    1,
+    8,
+    58,
+    2,
+    107,
+    7,
+    10,
+    1006,
+    0,
+    38,
+    2,
+    1008,
+    3,
+    10,
+    3,
+    8,
+    1002,
+    8,
+    -1,
+    10,
+    1001,
+    10,
+    1,
+    10,
+    4,
+    10,
+    108,
+    0,
+    8,
+    10,
+    4,
+    10,
+    1001,
+    8,
+    0,
+    90,
+    3,
+    8,
+    1002,
+    8,
+    -1,
+    10,
+    101,
+    1,
+    10,
+    10,
+    4,
+    10,
+    108,
+    0,
+    8,
+    10,
+    4,
+    10,
+    101,
+    0,
+    8,
+    112,
+    1006,
+    0,
+    65,
+    1,
+    1103,
+    1,
+    10,
+    1006,
+    0,
+    91,
+    3,
+    8,
+    102,
+    -1,
+    8,
+    10,
+    101,
+    1,
+    10,
+    10,
+    4,
+    10,
+    108,
+    1,
+    8,
+    10,
+    4,
+    10,
+    101,
+    0,
+    8,
+    144,
+    1006,
+    0,
+    32,
+    3,
+    8,
+    1002,
+    8,
+    -1,
+    10,
+    101,
+    1,
+    10,
+    10,
+    4,
+    10,
+    108,
+    1,
+    8,
+    10,
+    4,
+    10,
+    102,
+    1,
+    8,
+    169,
+    1,
+    109,
+    12,
+    10,
+    1006,
+    0,
+    96,
+    1006,
+    0,
+    5,
+    3,
+    8,
+    102,
+    -1,
+    8,
+    10,
+    1001,
+    10,
+    1,
+    10,
+    4,
+    10,
+    108,
+    1,
+    8,
+    10,
+    4,
+    10,
+    101,
+    0,
+    8,
+    201,
+    3,
+    8,
+    102,
+    -1,
+    8,
+    10,
+    1001,
+    10,
+    1,
+    10,
+    4,
+    10,
+    108,
+    0,
+    8,
+    10,
+    4,
+    10,
+    1001,
+    8,
+    0,
+    223,
+    1,
+    4,
+    9,
+    10,
+    2,
+    8,
+    5,
+    10,
+    1,
+    3,
+    4,
+    10,
+    3,
+    8,
+    1002,
+    8,
+    -1,
+    10,
+    1001,
+    10,
+    1,
+    10,
+    4,
+    10,
+    108,
+    1,
+    8,
+    10,
+    4,
+    10,
+    101,
+    0,
+    8,
+    257,
+    1,
+    1,
+    9,
+    10,
+    1006,
+    0,
+    87,
+    3,
+    8,
+    102,
+    -1,
+    8,
+    10,
+    1001,
+    10,
+    1,
+    10,
+    4,
+    10,
+    1008,
+    8,
+    0,
+    10,
+    4,
+    10,
+    102,
+    1,
+    8,
+    287,
+    2,
+    1105,
+    20,
+    10,
+    1,
+    1006,
+    3,
+    10,
+    1,
+    3,
+    4,
+    10,
+    101,
+    1,
+    9,
+    9,
+    1007,
+    9,
+    1002,
+    10,
+    1005,
+    10,
+    15,
+    99,
+    109,
+    641,
+    104,
+    0,
+    104,
+    1,
+    21102,
+    1,
+    932972962600,
+    1,
+    21101,
+    0,
+    336,
+    0,
+    1106,
+    0,
+    440,
+    21101,
+    838483681940,
+    0,
+    1,
+    21101,
+    0,
+    347,
+    0,
+    1106,
+    0,
+    440,
+    3,
+    10,
+    104,
+    0,
+    104,
+    1,
+    3,
+    10,
+    104,
+    0,
+    104,
+    0,
+    3,
+    10,
+    104,
+    0,
+    104,
+    1,
+    3,
+    10,
+    104,
+    0,
+    104,
+    1,
+    3,
+    10,
+    104,
+    0,
+    104,
+    0,
+    3,
+    10,
+    104,
+    0,
+    104,
+    1,
+    21101,
+    3375393987,
+    0,
+    1,
+    21101,
+    394,
+    0,
+    0,
+    1105,
+    1,
+    440,
+    21102,
+    46174071847,
+    1,
+    1,
+    21102,
+    1,
+    405,
+    0,
+    1106,
+    0,
+    440,
+    3,
+    10,
+    104,
+    0,
+    104,
+    0,
+    3,
+    10,
+    104,
+    0,
+    104,
+    0,
+    21101,
+    988648461076,
+    0,
+    1,
+    21101,
+    428,
+    0,
+    0,
+    1106,
+    0,
+    440,
+    21101,
+    0,
+    709580452200,
+    1,
+    21101,
+    439,
+    0,
+    0,
+    1105,
+    1,
+    440,
+    99,
+    109,
+    2,
+    22101,
+    0,
+    -1,
+    1,
+    21101,
+    40,
+    0,
+    2,
+    21102,
+    1,
+    471,
+    3,
+    21102,
+    461,
+    1,
+    0,
+    1106,
+    0,
+    504,
+    109,
+    -2,
+    2106,
+    0,
+    0,
+    0,
+    1,
+    0,
+    0,
+    1,
+    109,
+    2,
+    3,
+    10,
+    204,
+    -1,
+    1001,
+    466,
+    467,
+    482,
+    4,
+    0,
+    1001,
+    466,
+    1,
+    466,
+    108,
+    4,
+    466,
+    10,
+    1006,
+    10,
+    498,
+    1102,
+    0,
+    1,
+    466,
+    109,
+    -2,
+    2105,
+    1,
+    0,
+    0,
+    109,
+    4,
+    1202,
+    -1,
+    1,
+    503,
+    1207,
+    -3,
+    0,
+    10,
+    1006,
+    10,
+    521,
+    21102,
+    1,
+    0,
+    -3,
+    22102,
+    1,
+    -3,
+    1,
+    21201,
+    -2,
+    0,
+    2,
+    21101,
+    0,
+    1,
+    3,
+    21102,
+    540,
+    1,
+    0,
+    1106,
+    0,
+    545,
+    109,
+    -4,
+    2106,
+    0,
+    0,
+    109,
+    5,
+    1207,
+    -3,
+    1,
+    10,
+    1006,
+    10,
+    568,
+    2207,
+    -4,
+    -2,
+    10,
+    1006,
+    10,
+    568,
+    22101,
+    0,
+    -4,
+    -4,
+    1105,
+    1,
+    636,
+    22102,
+    1,
+    -4,
+    1,
+    21201,
+    -3,
+    -1,
+    2,
+    21202,
+    -2,
+    2,
+    3,
+    21102,
+    1,
+    587,
+    0,
+    1105,
+    1,
+    545,
+    22101,
+    0,
+    1,
+    -4,
+    21102,
+    1,
+    1,
+    -1,
+    2207,
+    -4,
+    -2,
+    10,
+    1006,
+    10,
+    606,
+    21101,
+    0,
+    0,
+    -1,
+    22202,
+    -2,
+    -1,
+    -2,
+    2107,
+    0,
+    -3,
+    10,
+    1006,
+    10,
+    628,
+    21201,
+    -1,
+    0,
+    1,
+    21101,
+    0,
+    628,
+    0,
+    106,
+    0,
+    503,
+    21202,
+    -2,
+    -1,
+    -2,
+    22201,
+    -4,
+    -2,
+    -4,
+    109,
+    -5,
+    2106,
+    0,
+    0,
+];
@@ -0,0 +1,177 @@
+use common::cycle::brent;
+use common::lcm;
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+pub const DAY: Day = Day {
+    number: 12,
+    input: DefaultInput::Text(INPUT),
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let mut moons = parse_moons(input.text());
+
+    for _ in 0..1000 {
+        update_moons(&mut moons);
+    }
+
+    compute_energy(&moons).into()
+}
+
+/// Steps until the moons repeat an earlier state.
+pub fn part2(input: &Input) -> Answer {
+    let moons = parse_moons(input.text());
+
+    // the axes are independent; the system repeats once all of them are in their loops and
+    // the loops line up
+    let cycles: Vec<_> = (0..3)
+        .map(|i| brent(&axis(&moons, i), |state| step_axis(state)))
+        .collect();
+    let tail = cycles.iter().map(|c| c.tail).max().unwrap() as i64;
+    let period = cycles.iter().fold(1, |p, c| lcm(p, c.period as i64));
+    (tail + period).into()
+}
+
+/// One moon per line, like `<x=-1, y=0, z=2>`.
+fn parse_moons(input: &str) -> Vec<Moon> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let coords: Vec<i32> = line
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .split(',')
+                .map(|c| c.split('=').nth(1).unwrap().trim().parse().unwrap())
+                .collect();
+            Moon::new(Vector::new(coords[0], coords[1], coords[2]))
+        })
+        .collect()
+}
+
+/// The positions and velocities of all moons along axis `i`.
+fn axis(moons: &[Moon], i: usize) -> Vec<(i32, i32)> {
+    moons.iter().map(|m| (m.pos.get(i), m.vel.get(i))).collect()
+}
+
+/// `update_moons` along one axis.
+fn step_axis(state: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    state
+        .iter()
+        .map(|&(pos, vel)| {
+            let vel = vel
+                + state
+                    .iter()
+                    .map(|&(other, _)| delta_velocity_scalar(pos, other))
+                    .sum::<i32>();
+            (pos + vel, vel)
+        })
+        .collect()
+}
+
+fn update_moons(moons: &mut [Moon]) {
+    let n = moons.len();
+    for i in 0..n {
+        for j in 0..n {
+            if i != j {
+                let pj = moons[j].pos;
+                moons[i].update_velocity(pj);
+            }
+        }
+    }
+
+    for m in moons {
+        m.update_position();
+    }
+}
+
+fn compute_energy(moons: &[Moon]) -> i32 {
+    let mut e = 0;
+    for m in moons {
+        let epot = m.pos.x.abs() + m.pos.y.abs() + m.pos.z.abs();
+        let ekin = m.vel.x.abs() + m.vel.y.abs() + m.vel.z.abs();
+        e += epot * ekin;
+    }
+    e
+}
+
+fn delta_velocity(p1: Vector, p2: Vector) -> Vector {
+    Vector {
+        x: delta_velocity_scalar(p1.x, p2.x),
+        y: delta_velocity_scalar(p1.y, p2.y),
+        z: delta_velocity_scalar(p1.z, p2.z),
+    }
+}
+
+fn delta_velocity_scalar(a: i32, b: i32) -> i32 {
+    match a.cmp(&b) {
+        std::cmp::Ordering::Equal => 0,
+        std::cmp::Ordering::Less => 1,
+        std::cmp::Ordering::Greater => -1,
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+struct Moon {
+    pos: Vector,
+    vel: Vector,
+}
+
+impl Moon {
+    fn new(pos: Vector) -> Self {
+        Moon {
+            pos,
+            vel: Vector::new(0, 0, 0),
+        }
+    }
+
+    fn update_velocity(&mut self, other_pos: Vector) {
+        self.vel = self.vel.add(delta_velocity(self.pos, other_pos))
+    }
+
+    fn update_position(&mut self) {
+        self.pos = self.pos.add(self.vel);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+struct Vector {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+impl Vector {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Vector { x, y, z }
+    }
+
+    pub fn add(self, other: Self) -> Self {
+        Vector {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> i32 {
+        match idx {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => panic!(),
+        }
+    }
+}
+
+const INPUT: &str = "<x=17, y=-9, z=4>
+<x=2, y=2, z=-13>
+<x=-1, y=5, z=-1>
+<x=4, y=7, z=-7>";
+
+/*const INPUT: &str = "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>";*/
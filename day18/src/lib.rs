@@ -0,0 +1,293 @@
+use common::backtracking::{self, BackTracking, Cheapest, Optimization, Transposition};
+use common::grid::{Direction, Grid, Pos};
+use common::runner::{Answer, Day, DefaultInput, Input};
+use common::search::Search;
+use std::collections::{HashMap, HashSet};
+
+pub const DAY: Day = Day {
+    number: 18,
+    input: DefaultInput::File,
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let (map, start_pos) = parse_map(input.text());
+    collect_keys(&map, vec![start_pos]).into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let (mut map, start_pos) = parse_map(input.text());
+    let starts = split_vault(&mut map, start_pos);
+    collect_keys(&map, starts).into()
+}
+
+/// Wall in the center of the vault and put a robot in each quadrant; returns their positions.
+fn split_vault(map: &mut Grid<Tile>, start_pos: Pos) -> Vec<Pos> {
+    let p1 = start_pos + Direction::North + Direction::East;
+    let p2 = start_pos + Direction::North + Direction::West;
+    let p3 = start_pos + Direction::South + Direction::East;
+    let p4 = start_pos + Direction::South + Direction::West;
+
+    map[start_pos] = Tile::Wall;
+    map[start_pos + Direction::North] = Tile::Wall;
+    map[start_pos + Direction::South] = Tile::Wall;
+    map[start_pos + Direction::East] = Tile::Wall;
+    map[start_pos + Direction::West] = Tile::Wall;
+    map[p1] = Tile::Start(1);
+    map[p2] = Tile::Start(2);
+    map[p3] = Tile::Start(3);
+    map[p4] = Tile::Start(4);
+
+    vec![p1, p2, p3, p4]
+}
+
+/// The fewest steps for the robots starting at `starts` to collect all keys.
+fn collect_keys(map: &Grid<Tile>, starts: Vec<Pos>) -> usize {
+    let robots = starts.iter().map(|&p| map[p]).collect();
+    let edges = build_mapgraph(starts, map);
+    let mut search = Cheapest::new(PathSearch::new(edges, robots));
+    let (best, _) = backtracking::Search::new()
+        .stop_after(1)
+        .best_first(&mut search);
+    best.expect("some keys cannot be reached")
+}
+
+/// Where the robots are, which keys they have collected and how many steps that took.
+#[derive(Debug, Clone)]
+struct Progress {
+    robots: Vec<Tile>,
+    keys: u32,
+    steps: usize,
+}
+
+struct PathSearch {
+    neighbors: HashMap<Tile, Vec<(Tile, usize)>>,
+    all_keys: u32,
+    robots: Vec<Tile>,
+}
+
+impl PathSearch {
+    fn new(map: HashMap<Tile, Vec<(Tile, usize)>>, robots: Vec<Tile>) -> Self {
+        PathSearch {
+            all_keys: map
+                .values()
+                .flatten()
+                .map(|&(tile, _)| key_bit(tile))
+                .fold(0, |a, b| a | b),
+            neighbors: map,
+            robots,
+        }
+    }
+
+    fn dijkstra_search(&self, tile: Tile, from: Tile, keys: u32) -> Option<usize> {
+        Search::new(vec![from])
+            .dijkstra(
+                |u| {
+                    self.neighbors
+                        .get(u)
+                        .into_iter()
+                        .flatten()
+                        .copied()
+                        .filter(|&(v, _)| self.passable(v, keys))
+                },
+                |&u| u == tile,
+            )
+            .map(|found| found.cost)
+    }
+
+    fn passable(&self, tile: Tile, keys: u32) -> bool {
+        if let Tile::Door(ch) = tile {
+            keys & key_bit(Tile::Key(ch.to_ascii_lowercase())) != 0
+        } else {
+            true
+        }
+    }
+}
+
+impl BackTracking for PathSearch {
+    type PartialCandidate = Progress;
+
+    fn output(&mut self, _: &Progress) {}
+
+    fn root(&self) -> Progress {
+        Progress {
+            robots: self.robots.clone(),
+            keys: 0,
+            steps: 0,
+        }
+    }
+
+    fn reject(&self, _: &Progress) -> bool {
+        false
+    }
+
+    fn accept(&self, c: &Progress) -> bool {
+        c.keys == self.all_keys
+    }
+
+    /// Send one of the robots to a key it can reach.
+    fn extend(&self, c: &Progress) -> Box<dyn Iterator<Item = Progress>> {
+        let mut next = vec![];
+        for bit in (0..26).map(|i| 1 << i) {
+            if self.all_keys & bit == 0 || c.keys & bit != 0 {
+                continue;
+            }
+            let key = Tile::Key((b'a' + bit.trailing_zeros() as u8) as char);
+            for (i, &robot) in c.robots.iter().enumerate() {
+                if let Some(n) = self.dijkstra_search(key, robot, c.keys) {
+                    let mut robots = c.robots.clone();
+                    robots[i] = key;
+                    next.push(Progress {
+                        robots,
+                        keys: c.keys | bit,
+                        steps: c.steps + n,
+                    });
+                    break;
+                }
+            }
+        }
+        Box::new(next.into_iter())
+    }
+}
+
+impl Optimization for PathSearch {
+    type Cost = usize;
+
+    fn cost(&self, c: &Progress) -> usize {
+        c.steps
+    }
+}
+
+/// The steps taken so far are left out; `Cheapest` continues from the fewest.
+impl Transposition for PathSearch {
+    type Key = (Vec<Tile>, u32);
+
+    fn key(&self, c: &Progress) -> Self::Key {
+        (c.robots.clone(), c.keys)
+    }
+}
+
+fn key_bit(tile: Tile) -> u32 {
+    match tile {
+        Tile::Key(ch) => 1 << (ch as u8 - b'a'),
+        _ => 0,
+    }
+}
+
+fn build_mapgraph(positions: Vec<Pos>, map: &Grid<Tile>) -> HashMap<Tile, Vec<(Tile, usize)>> {
+    let mut nodes = HashSet::new();
+    let mut edges = HashMap::new();
+
+    for &pos in &positions {
+        nodes.insert(pos);
+    }
+
+    let mut queue = positions;
+    while let Some(pos) = queue.pop() {
+        //println!("{:?}", pos);
+        for (tile, dist) in find_all_reachable(pos, map) {
+            edges
+                .entry(map[pos])
+                .or_insert(vec![])
+                .push((map[tile], dist));
+
+            if !nodes.contains(&tile) {
+                queue.push(tile);
+                nodes.insert(tile);
+            }
+        }
+    }
+
+    edges
+}
+
+/// The keys and doors reachable from `start_pos` without passing another key or door.
+fn find_all_reachable(start_pos: Pos, map: &Grid<Tile>) -> Vec<(Pos, usize)> {
+    Search::new(vec![start_pos])
+        .reachable(|&pos| match map[pos] {
+            Tile::Wall => vec![],
+            Tile::Key(_) | Tile::Door(_) if pos != start_pos => vec![],
+            _ => pos.neighbors().collect(),
+        })
+        .filter(|&(pos, _)| pos != start_pos)
+        .filter(|(pos, _)| matches!(map[*pos], Tile::Key(_) | Tile::Door(_)))
+        .collect()
+}
+
+fn parse_map(input: &str) -> (Grid<Tile>, Pos) {
+    let map = Grid::parse(input, Tile::from).unwrap();
+    let start_pos = map.find(|&tile| tile == Tile::Start(0)).unwrap();
+    (map, start_pos)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+enum Tile {
+    Empty,
+    Start(u8),
+    Wall,
+    Door(char),
+    Key(char),
+}
+
+impl From<char> for Tile {
+    fn from(ch: char) -> Self {
+        match ch {
+            '@' => Tile::Start(0),
+            '.' => Tile::Empty,
+            '#' => Tile::Wall,
+            ch if ch.is_ascii_uppercase() => Tile::Door(ch),
+            ch if ch.is_ascii_lowercase() => Tile::Key(ch),
+            _ => panic!("invalid tile: {}", ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example18_1() {
+        let input = "#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################";
+        let (map, start_pos) = parse_map(input);
+        assert_eq!(collect_keys(&map, vec![start_pos]), 136);
+    }
+
+    #[test]
+    fn example18_2() {
+        let input = "#######
+#a.#Cd#
+##...##
+##.@.##
+##...##
+#cB#Ab#
+#######";
+        let (mut map, start_pos) = parse_map(input);
+        let starts = split_vault(&mut map, start_pos);
+        assert_eq!(collect_keys(&map, starts), 8);
+    }
+
+    #[test]
+    fn depth_first_finds_fewest_steps() {
+        let input = "########################
+#@..............ac.GI.b#
+###d#e#f################
+###A#B#C################
+###g#h#i################
+########################";
+        let (map, start_pos) = parse_map(input);
+        let edges = build_mapgraph(vec![start_pos], &map);
+        let mut search = Cheapest::new(PathSearch::new(edges, vec![Tile::Start(0)]));
+        let (best, _) = backtracking::Search::new().branch_and_bound(&mut search);
+        assert_eq!(best, Some(81));
+    }
+}
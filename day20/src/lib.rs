@@ -0,0 +1,268 @@
+use common::grid::{Direction, Grid, Pos};
+use common::runner::{Answer, Day, DefaultInput, Input};
+use common::search::Search;
+use std::collections::{HashMap, HashSet};
+
+pub const DAY: Day = Day {
+    number: 20,
+    input: DefaultInput::File,
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let (map, start_pos) = parse_map(input.text());
+    breadth_first_search(start_pos, &map).into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let (map, start_pos) = parse_map(input.text());
+    recursive_breadth_first_search(start_pos, &map).into()
+}
+
+fn recursive_breadth_first_search(start: Pos, map: &Grid<Tile>) -> usize {
+    let moves = |&(level, pos): &(i32, Pos)| {
+        pos.neighbors()
+            .filter_map(move |next| match map.get(next).unwrap_or(&Tile::Wall) {
+                Tile::Wall => None,
+                Tile::Exit if level != 0 => None,
+                Tile::Outer(_) if level == 0 => None,
+                Tile::Inner(out) => Some((level + 1, *out)),
+                Tile::Outer(out) => Some((level - 1, *out)),
+                _ => Some((level, next)),
+            })
+    };
+    let exit = |&(_, pos): &(i32, Pos)| map[pos] == Tile::Exit;
+    // remove one step for entry and one step for exit
+    Search::new(vec![(0, start)]).bfs(moves, exit).unwrap().cost - 2
+}
+
+fn breadth_first_search(start: Pos, map: &Grid<Tile>) -> usize {
+    let moves = |&pos: &Pos| {
+        pos.neighbors()
+            .filter_map(move |next| match map.get(next).unwrap_or(&Tile::Wall) {
+                Tile::Wall => None,
+                Tile::Outer(out) | Tile::Inner(out) => Some(*out),
+                _ => Some(next),
+            })
+    };
+    let exit = |pos: &Pos| map[*pos] == Tile::Exit;
+    // remove one step for entry and one step for exit
+    Search::new(vec![start]).bfs(moves, exit).unwrap().cost - 2
+}
+
+fn parse_map(input: &str) -> (Grid<Tile>, Pos) {
+    let chars = Grid::parse_chars(input).unwrap();
+
+    let width = chars.width();
+    let height = chars.height();
+
+    let mut portals: HashMap<String, HashSet<Pos>> = HashMap::new();
+
+    for i in 0..height - 1 {
+        for j in 0..width - 1 {
+            if chars[grid_pos(j, i)].is_alphabetic() {
+                if chars[grid_pos(j, i + 1)].is_alphabetic() {
+                    let name = format!("{}{}", chars[grid_pos(j, i)], chars[grid_pos(j, i + 1)]);
+                    if i == 0 {
+                        portals.entry(name).or_default().insert(grid_pos(j, 1));
+                    } else if i == height - 2 || chars[grid_pos(j, i - 1)] == '.' {
+                        portals.entry(name).or_default().insert(grid_pos(j, i));
+                    } else if chars[grid_pos(j, i + 2)] == '.' {
+                        portals.entry(name).or_default().insert(grid_pos(j, i + 1));
+                    }
+                }
+
+                if chars[grid_pos(j + 1, i)].is_alphabetic() {
+                    let name = format!("{}{}", chars[grid_pos(j, i)], chars[grid_pos(j + 1, i)]);
+                    if j == 0 {
+                        portals.entry(name).or_default().insert(grid_pos(1, i));
+                    } else if j == width - 2 || chars[grid_pos(j - 1, i)] == '.' {
+                        portals.entry(name).or_default().insert(grid_pos(j, i));
+                    } else if chars[grid_pos(j + 2, i)] == '.' {
+                        portals.entry(name).or_default().insert(grid_pos(j + 1, i));
+                    }
+                }
+            }
+        }
+    }
+
+    let portal_positions: HashMap<_, _> = portals
+        .iter()
+        .flat_map(|(name, positions)| positions.iter().map(move |pos| (*pos, name.clone())))
+        .collect();
+
+    let mut tiles = Grid::new(width, height, Tile::Wall);
+
+    let mut entry = Pos::default();
+
+    for i in 1..height - 1 {
+        for j in 1..width - 1 {
+            let pos = grid_pos(j, i);
+            if let Some(name) = portal_positions.get(&pos) {
+                match name.as_str() {
+                    "AA" => {
+                        entry = pos;
+                        tiles[pos] = Tile::Entry
+                    }
+                    "ZZ" => tiles[pos] = Tile::Exit,
+                    _ => {
+                        let other = *portals[name].iter().find(|p| **p != pos).unwrap();
+                        let out = [
+                            Direction::North,
+                            Direction::South,
+                            Direction::East,
+                            Direction::West,
+                        ]
+                        .iter()
+                        .map(|&d| other + d)
+                        .find(|p| chars[*p] == '.')
+                        .unwrap();
+                        if i < 2 || j < 2 || i > height - 3 || j > width - 3 {
+                            tiles[pos] = Tile::Outer(out)
+                        } else {
+                            tiles[pos] = Tile::Inner(out)
+                        }
+                    }
+                };
+            } else {
+                match chars[grid_pos(j, i)] {
+                    '#' => {
+                        tiles[pos] = Tile::Wall;
+                    }
+                    '.' => {
+                        tiles[pos] = Tile::Empty;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    (tiles, entry)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Tile {
+    Empty,
+    Wall,
+    Outer(Pos),
+    Inner(Pos),
+    Entry,
+    Exit,
+}
+
+impl From<char> for Tile {
+    fn from(ch: char) -> Self {
+        match ch {
+            '.' => Tile::Empty,
+            '#' => Tile::Wall,
+            _ => panic!("invalid tile: {}", ch),
+        }
+    }
+}
+
+fn grid_pos(x: usize, y: usize) -> Pos {
+    Pos::new(x as i64, y as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example20_1() {
+        let input = "         A    #######
+         A    #######
+  #######.###########
+  #######.........###
+  #######.#######.###
+  #######.#######.###
+  #######.#######.###
+  #####  B    ###.###
+BC...##  C    ###.###
+  ##.##       ###.###
+  ##...DE  F  ###.###
+  #####    G  ###.###
+  #########.#####.###
+DE..#######...###.###
+  #.#########.###.###
+FG..#########.....###
+  ###########.#######
+             Z    ###
+             Z     ##";
+
+        let (map, start_pos) = parse_map(&input);
+        assert_eq!(breadth_first_search(start_pos, &map), 23);
+    }
+
+    #[test]
+    fn example20_2() {
+        let input = "                   A  #############
+                   A  #############
+  #################.###############
+  #.#...#...................#.#.###
+  #.#.#.###.###.###.#########.#.###
+  #.#.#.......#...#.....#.#.#...###
+  #.#########.###.#####.#.#.###.###
+  #.............#.#.....#.......###
+  ###.###########.###.#####.#.#.###
+  #.....#        A   C    #.#.#.###
+  #######        S   P    #####.###
+  #.#...#                 #......VT
+  #.#.#.#                 #.#######
+  #...#.#               YN....#.###
+  #.###.#                 #####.###
+DI....#.#                 #.....###
+  #####.#                 #.###.###
+ZZ......#               QG....#..AS
+  ###.###                 #########
+JO..#.#.#                 #.....###
+  #.#.#.#                 ###.#.###
+  #...#..DI             BU....#..LF
+  #####.#                 #.#######
+YN......#               VT..#....QG
+  #.###.#                 #.###.###
+  #.#...#                 #.....###
+  ###.###    J L     J    #.#.#####
+  #.....#    O F     P    #.#...###
+  #.###.#####.#.#####.#####.###.###
+  #...#.#.#...#.....#.....#.#...###
+  #.#####.###.###.#.#.#########.###
+  #...#.#.....#...#.#.#.#.....#.###
+  #.###.#####.###.###.#.#.#########
+  #.#.........#...#.............###
+  #########.###.###.###############
+           B   J   C  #############
+           U   P   P               ";
+
+        let (map, start_pos) = parse_map(&input);
+        assert_eq!(breadth_first_search(start_pos, &map), 58);
+    }
+
+    #[test]
+    fn example20_3() {
+        let input = "         A    #######
+         A    #######
+  #######.###########
+  #######.........###
+  #######.#######.###
+  #######.#######.###
+  #######.#######.###
+  #####  B    ###.###
+BC...##  C    ###.###
+  ##.##       ###.###
+  ##...DE  F  ###.###
+  #####    G  ###.###
+  #########.#####.###
+DE..#######...###.###
+  #.#########.###.###
+FG..#########.....###
+  ###########.#######
+             Z    ###
+             Z     ##";
+
+        let (map, start_pos) = parse_map(&input);
+        assert_eq!(recursive_breadth_first_search(start_pos, &map), 26);
+    }
+}
@@ -1,257 +1,3 @@
-use common::intcode2::Computer;
-
-fn main() {
-    use Reg::*;
-    use SCI::*;
-    // only jump if we can land safely (at D) and there is a hole anywhere before D
-    if let Some(n) = run_script(
-        &[Or(A, J), And(B, J), And(C, J), Not(J, J), And(D, J)],
-        false,
-        true,
-    ) {
-        println!("Part 1: {}", n)
-    }
-
-    /* didn't need this part after all...
-    let mut conditions = HashMap::new();
-
-    for i in 0..512 {
-        let mask = format!("{:#012b}", i+512);
-        let path: String = mask.chars().skip(2).map(|ch| match ch { '0' => '.', '1' => '#', _ => panic!() }).collect();
-        let platform: Vec<_> = path.chars().map(|ch| ch != '.').collect();
-        let r = simulate(&platform);
-        conditions.entry(r).or_insert(vec![]).push(path);
-    }
-
-    for c in &[(false, false), (true, true), (false, true), (true, false)] {
-        println!("{:?}", c);
-        for p in &conditions[c] {
-            println!("   {}", p);
-        }
-    }
-    */
-
-    // Fields
-    // #####.###########
-    // #####.#..########
-    // #####.##.########
-    // #####..#.########
-
-    // Don't Jump
-    // ####.#####
-    // #####.#..#
-    // ####.#..##
-    //  ABCDEFGHI
-
-    // Jump
-    // #.########
-    // ###.#..###
-    // ##..#.####
-    //  ABCDEFGHI
-
-    // only jump if we can land safely at D and again at H, and there is a hole anywhere before D
-    if let Some(n) = run_script(
-        &[
-            Not(A, J),
-            Or(D, T),
-            And(D, T),
-            And(H, T),
-            Or(T, J),
-            Or(A, T),
-            And(A, T),
-            And(B, T),
-            And(C, T),
-            Not(T, T),
-            And(T, J),
-        ],
-        true,
-        true,
-    ) {
-        println!("Part 2: {}", n)
-    }
+fn main() -> Result<(), common::input::InputError> {
+    common::runner::main(&day21::DAY)
 }
-
-fn run_script(program: &[SCI], run: bool, show: bool) -> Option<i64> {
-    let ascii: Vec<_> = program.iter().map(SCI::to_string).collect();
-    let mut ascii = ascii.join("\n");
-    if run {
-        ascii += "\nRUN\n";
-    } else {
-        ascii += "\nWALK\n";
-    }
-
-    let mut vm = Computer::new(&INPUT);
-    let r = vm.map(ascii.bytes().map(|b| b as i64)).unwrap();
-    if *r.last().unwrap() > 255 {
-        Some(*r.last().unwrap())
-    } else {
-        if show {
-            for &i in &r {
-                print!("{}", i as u8 as char);
-            }
-        }
-        None
-    }
-}
-
-enum SCI {
-    And(Reg, Reg),
-    Or(Reg, Reg),
-    Not(Reg, Reg),
-}
-
-impl std::fmt::Display for SCI {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            SCI::And(a, b) => write!(f, "AND {:?} {:?}", a, b),
-            SCI::Or(a, b) => write!(f, "OR {:?} {:?}", a, b),
-            SCI::Not(a, b) => write!(f, "NOT {:?} {:?}", a, b),
-        }
-    }
-}
-
-#[allow(dead_code)]
-fn simulate(platform: &[bool]) -> (bool, bool) {
-    if platform.is_empty() {
-        return (true, true);
-    }
-
-    if !platform[0] {
-        return (false, false);
-    }
-
-    let no_jump = {
-        let (a, b) = simulate(&platform[1..]);
-        a || b
-    };
-
-    let with_jump = {
-        if platform.len() < 5 {
-            true
-        } else {
-            let (a, b) = simulate(&platform[4..]);
-            a || b
-        }
-    };
-
-    (with_jump, no_jump)
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
-enum Reg {
-    A,
-    B,
-    C,
-    D,
-    E,
-    F,
-    G,
-    H,
-    I,
-    T,
-    J,
-}
-
-const INPUT: [i64; 2050] = [
-    109, 2050, 21101, 966, 0, 1, 21101, 0, 13, 0, 1105, 1, 1378, 21101, 0, 20, 0, 1105, 1, 1337,
-    21101, 27, 0, 0, 1106, 0, 1279, 1208, 1, 65, 748, 1005, 748, 73, 1208, 1, 79, 748, 1005, 748,
-    110, 1208, 1, 78, 748, 1005, 748, 132, 1208, 1, 87, 748, 1005, 748, 169, 1208, 1, 82, 748,
-    1005, 748, 239, 21102, 1, 1041, 1, 21101, 73, 0, 0, 1105, 1, 1421, 21102, 1, 78, 1, 21101, 0,
-    1041, 2, 21101, 88, 0, 0, 1105, 1, 1301, 21102, 1, 68, 1, 21101, 0, 1041, 2, 21102, 1, 103, 0,
-    1105, 1, 1301, 1102, 1, 1, 750, 1106, 0, 298, 21102, 1, 82, 1, 21102, 1, 1041, 2, 21102, 1,
-    125, 0, 1105, 1, 1301, 1102, 1, 2, 750, 1106, 0, 298, 21101, 0, 79, 1, 21102, 1041, 1, 2,
-    21101, 0, 147, 0, 1105, 1, 1301, 21101, 84, 0, 1, 21102, 1041, 1, 2, 21101, 162, 0, 0, 1106, 0,
-    1301, 1101, 3, 0, 750, 1105, 1, 298, 21102, 65, 1, 1, 21101, 1041, 0, 2, 21102, 1, 184, 0,
-    1106, 0, 1301, 21101, 0, 76, 1, 21101, 0, 1041, 2, 21101, 199, 0, 0, 1106, 0, 1301, 21102, 75,
-    1, 1, 21102, 1041, 1, 2, 21102, 1, 214, 0, 1106, 0, 1301, 21101, 221, 0, 0, 1105, 1, 1337,
-    21101, 10, 0, 1, 21101, 1041, 0, 2, 21101, 0, 236, 0, 1105, 1, 1301, 1106, 0, 553, 21101, 85,
-    0, 1, 21102, 1041, 1, 2, 21101, 0, 254, 0, 1106, 0, 1301, 21101, 0, 78, 1, 21101, 0, 1041, 2,
-    21102, 1, 269, 0, 1106, 0, 1301, 21101, 0, 276, 0, 1105, 1, 1337, 21102, 10, 1, 1, 21101, 0,
-    1041, 2, 21102, 1, 291, 0, 1106, 0, 1301, 1102, 1, 1, 755, 1106, 0, 553, 21102, 1, 32, 1,
-    21102, 1041, 1, 2, 21102, 1, 313, 0, 1106, 0, 1301, 21101, 320, 0, 0, 1105, 1, 1337, 21102,
-    327, 1, 0, 1105, 1, 1279, 1202, 1, 1, 749, 21101, 65, 0, 2, 21102, 1, 73, 3, 21102, 1, 346, 0,
-    1106, 0, 1889, 1206, 1, 367, 1007, 749, 69, 748, 1005, 748, 360, 1101, 1, 0, 756, 1001, 749,
-    -64, 751, 1106, 0, 406, 1008, 749, 74, 748, 1006, 748, 381, 1101, -1, 0, 751, 1105, 1, 406,
-    1008, 749, 84, 748, 1006, 748, 395, 1102, -2, 1, 751, 1105, 1, 406, 21102, 1100, 1, 1, 21102,
-    1, 406, 0, 1105, 1, 1421, 21102, 32, 1, 1, 21102, 1, 1100, 2, 21102, 1, 421, 0, 1105, 1, 1301,
-    21101, 0, 428, 0, 1105, 1, 1337, 21101, 0, 435, 0, 1105, 1, 1279, 2101, 0, 1, 749, 1008, 749,
-    74, 748, 1006, 748, 453, 1102, -1, 1, 752, 1105, 1, 478, 1008, 749, 84, 748, 1006, 748, 467,
-    1102, 1, -2, 752, 1106, 0, 478, 21102, 1, 1168, 1, 21101, 478, 0, 0, 1106, 0, 1421, 21102, 485,
-    1, 0, 1106, 0, 1337, 21102, 10, 1, 1, 21102, 1, 1168, 2, 21102, 500, 1, 0, 1106, 0, 1301, 1007,
-    920, 15, 748, 1005, 748, 518, 21102, 1, 1209, 1, 21101, 518, 0, 0, 1106, 0, 1421, 1002, 920, 3,
-    529, 1001, 529, 921, 529, 1002, 750, 1, 0, 1001, 529, 1, 537, 1001, 751, 0, 0, 1001, 537, 1,
-    545, 101, 0, 752, 0, 1001, 920, 1, 920, 1105, 1, 13, 1005, 755, 577, 1006, 756, 570, 21101,
-    1100, 0, 1, 21102, 1, 570, 0, 1106, 0, 1421, 21101, 987, 0, 1, 1105, 1, 581, 21101, 0, 1001, 1,
-    21101, 0, 588, 0, 1106, 0, 1378, 1101, 758, 0, 593, 1001, 0, 0, 753, 1006, 753, 654, 20102, 1,
-    753, 1, 21102, 1, 610, 0, 1105, 1, 667, 21101, 0, 0, 1, 21102, 621, 1, 0, 1105, 1, 1463, 1205,
-    1, 647, 21102, 1015, 1, 1, 21102, 1, 635, 0, 1106, 0, 1378, 21102, 1, 1, 1, 21102, 646, 1, 0,
-    1105, 1, 1463, 99, 1001, 593, 1, 593, 1106, 0, 592, 1006, 755, 664, 1102, 0, 1, 755, 1105, 1,
-    647, 4, 754, 99, 109, 2, 1101, 726, 0, 757, 22101, 0, -1, 1, 21102, 9, 1, 2, 21102, 1, 697, 3,
-    21102, 692, 1, 0, 1106, 0, 1913, 109, -2, 2106, 0, 0, 109, 2, 1002, 757, 1, 706, 1201, -1, 0,
-    0, 1001, 757, 1, 757, 109, -2, 2105, 1, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 63, 127, 159,
-    223, 95, 191, 0, 115, 111, 49, 242, 93, 216, 232, 100, 183, 152, 92, 68, 206, 234, 167, 114,
-    182, 39, 243, 158, 236, 197, 213, 228, 94, 252, 154, 141, 76, 101, 103, 51, 166, 99, 249, 188,
-    137, 204, 241, 162, 190, 35, 109, 156, 43, 47, 175, 200, 120, 79, 86, 202, 87, 125, 231, 59,
-    173, 227, 126, 142, 116, 170, 34, 136, 233, 222, 218, 207, 119, 189, 53, 217, 230, 157, 205,
-    138, 199, 214, 121, 201, 50, 178, 247, 235, 220, 113, 254, 78, 85, 102, 238, 179, 42, 187, 110,
-    118, 107, 60, 172, 61, 221, 169, 226, 203, 177, 248, 124, 54, 239, 143, 77, 38, 123, 71, 245,
-    98, 237, 84, 58, 55, 185, 69, 139, 56, 155, 196, 250, 62, 246, 122, 163, 244, 140, 184, 174,
-    186, 181, 212, 198, 168, 253, 215, 46, 106, 219, 70, 57, 117, 153, 251, 229, 108, 171, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 73, 110, 112, 117, 116, 32, 105, 110, 115, 116, 114,
-    117, 99, 116, 105, 111, 110, 115, 58, 10, 13, 10, 87, 97, 108, 107, 105, 110, 103, 46, 46, 46,
-    10, 10, 13, 10, 82, 117, 110, 110, 105, 110, 103, 46, 46, 46, 10, 10, 25, 10, 68, 105, 100,
-    110, 39, 116, 32, 109, 97, 107, 101, 32, 105, 116, 32, 97, 99, 114, 111, 115, 115, 58, 10, 10,
-    58, 73, 110, 118, 97, 108, 105, 100, 32, 111, 112, 101, 114, 97, 116, 105, 111, 110, 59, 32,
-    101, 120, 112, 101, 99, 116, 101, 100, 32, 115, 111, 109, 101, 116, 104, 105, 110, 103, 32,
-    108, 105, 107, 101, 32, 65, 78, 68, 44, 32, 79, 82, 44, 32, 111, 114, 32, 78, 79, 84, 67, 73,
-    110, 118, 97, 108, 105, 100, 32, 102, 105, 114, 115, 116, 32, 97, 114, 103, 117, 109, 101, 110,
-    116, 59, 32, 101, 120, 112, 101, 99, 116, 101, 100, 32, 115, 111, 109, 101, 116, 104, 105, 110,
-    103, 32, 108, 105, 107, 101, 32, 65, 44, 32, 66, 44, 32, 67, 44, 32, 68, 44, 32, 74, 44, 32,
-    111, 114, 32, 84, 40, 73, 110, 118, 97, 108, 105, 100, 32, 115, 101, 99, 111, 110, 100, 32, 97,
-    114, 103, 117, 109, 101, 110, 116, 59, 32, 101, 120, 112, 101, 99, 116, 101, 100, 32, 74, 32,
-    111, 114, 32, 84, 52, 79, 117, 116, 32, 111, 102, 32, 109, 101, 109, 111, 114, 121, 59, 32, 97,
-    116, 32, 109, 111, 115, 116, 32, 49, 53, 32, 105, 110, 115, 116, 114, 117, 99, 116, 105, 111,
-    110, 115, 32, 99, 97, 110, 32, 98, 101, 32, 115, 116, 111, 114, 101, 100, 0, 109, 1, 1005,
-    1262, 1270, 3, 1262, 20101, 0, 1262, 0, 109, -1, 2105, 1, 0, 109, 1, 21101, 0, 1288, 0, 1106,
-    0, 1263, 21001, 1262, 0, 0, 1101, 0, 0, 1262, 109, -1, 2105, 1, 0, 109, 5, 21102, 1, 1310, 0,
-    1105, 1, 1279, 21201, 1, 0, -2, 22208, -2, -4, -1, 1205, -1, 1332, 21202, -3, 1, 1, 21101, 0,
-    1332, 0, 1106, 0, 1421, 109, -5, 2106, 0, 0, 109, 2, 21101, 0, 1346, 0, 1106, 0, 1263, 21208,
-    1, 32, -1, 1205, -1, 1363, 21208, 1, 9, -1, 1205, -1, 1363, 1106, 0, 1373, 21101, 1370, 0, 0,
-    1105, 1, 1279, 1105, 1, 1339, 109, -2, 2106, 0, 0, 109, 5, 1202, -4, 1, 1386, 20102, 1, 0, -2,
-    22101, 1, -4, -4, 21101, 0, 0, -3, 22208, -3, -2, -1, 1205, -1, 1416, 2201, -4, -3, 1408, 4, 0,
-    21201, -3, 1, -3, 1106, 0, 1396, 109, -5, 2106, 0, 0, 109, 2, 104, 10, 21202, -1, 1, 1, 21101,
-    0, 1436, 0, 1106, 0, 1378, 104, 10, 99, 109, -2, 2105, 1, 0, 109, 3, 20002, 593, 753, -1,
-    22202, -1, -2, -1, 201, -1, 754, 754, 109, -3, 2105, 1, 0, 109, 10, 21101, 5, 0, -5, 21102, 1,
-    1, -4, 21101, 0, 0, -3, 1206, -9, 1555, 21102, 1, 3, -6, 21102, 1, 5, -7, 22208, -7, -5, -8,
-    1206, -8, 1507, 22208, -6, -4, -8, 1206, -8, 1507, 104, 64, 1105, 1, 1529, 1205, -6, 1527,
-    1201, -7, 716, 1515, 21002, 0, -11, -8, 21201, -8, 46, -8, 204, -8, 1106, 0, 1529, 104, 46,
-    21201, -7, 1, -7, 21207, -7, 22, -8, 1205, -8, 1488, 104, 10, 21201, -6, -1, -6, 21207, -6, 0,
-    -8, 1206, -8, 1484, 104, 10, 21207, -4, 1, -8, 1206, -8, 1569, 21102, 0, 1, -9, 1105, 1, 1689,
-    21208, -5, 21, -8, 1206, -8, 1583, 21101, 0, 1, -9, 1105, 1, 1689, 1201, -5, 716, 1589, 20101,
-    0, 0, -2, 21208, -4, 1, -1, 22202, -2, -1, -1, 1205, -2, 1613, 22101, 0, -5, 1, 21101, 0, 1613,
-    0, 1105, 1, 1444, 1206, -1, 1634, 21202, -5, 1, 1, 21101, 1627, 0, 0, 1105, 1, 1694, 1206, 1,
-    1634, 21101, 0, 2, -3, 22107, 1, -4, -8, 22201, -1, -8, -8, 1206, -8, 1649, 21201, -5, 1, -5,
-    1206, -3, 1663, 21201, -3, -1, -3, 21201, -4, 1, -4, 1105, 1, 1667, 21201, -4, -1, -4, 21208,
-    -4, 0, -1, 1201, -5, 716, 1676, 22002, 0, -1, -1, 1206, -1, 1686, 21101, 1, 0, -4, 1105, 1,
-    1477, 109, -10, 2106, 0, 0, 109, 11, 21102, 1, 0, -6, 21101, 0, 0, -8, 21101, 0, 0, -7, 20208,
-    -6, 920, -9, 1205, -9, 1880, 21202, -6, 3, -9, 1201, -9, 921, 1725, 20101, 0, 0, -5, 1001,
-    1725, 1, 1733, 20102, 1, 0, -4, 22102, 1, -4, 1, 21102, 1, 1, 2, 21101, 9, 0, 3, 21102, 1754,
-    1, 0, 1106, 0, 1889, 1206, 1, 1772, 2201, -10, -4, 1767, 1001, 1767, 716, 1767, 20102, 1, 0,
-    -3, 1105, 1, 1790, 21208, -4, -1, -9, 1206, -9, 1786, 22101, 0, -8, -3, 1106, 0, 1790, 22102,
-    1, -7, -3, 1001, 1733, 1, 1796, 20101, 0, 0, -2, 21208, -2, -1, -9, 1206, -9, 1812, 21201, -8,
-    0, -1, 1105, 1, 1816, 21202, -7, 1, -1, 21208, -5, 1, -9, 1205, -9, 1837, 21208, -5, 2, -9,
-    1205, -9, 1844, 21208, -3, 0, -1, 1106, 0, 1855, 22202, -3, -1, -1, 1106, 0, 1855, 22201, -3,
-    -1, -1, 22107, 0, -1, -1, 1106, 0, 1855, 21208, -2, -1, -9, 1206, -9, 1869, 22101, 0, -1, -8,
-    1106, 0, 1873, 21202, -1, 1, -7, 21201, -6, 1, -6, 1105, 1, 1708, 21202, -8, 1, -10, 109, -11,
-    2106, 0, 0, 109, 7, 22207, -6, -5, -3, 22207, -4, -6, -2, 22201, -3, -2, -1, 21208, -1, 0, -6,
-    109, -7, 2106, 0, 0, 0, 109, 5, 1201, -2, 0, 1912, 21207, -4, 0, -1, 1206, -1, 1930, 21101, 0,
-    0, -4, 21202, -4, 1, 1, 21202, -3, 1, 2, 21102, 1, 1, 3, 21102, 1, 1949, 0, 1105, 1, 1954, 109,
-    -5, 2106, 0, 0, 109, 6, 21207, -4, 1, -1, 1206, -1, 1977, 22207, -5, -3, -1, 1206, -1, 1977,
-    22101, 0, -5, -5, 1106, 0, 2045, 22102, 1, -5, 1, 21201, -4, -1, 2, 21202, -3, 2, 3, 21101, 0,
-    1996, 0, 1106, 0, 1954, 22102, 1, 1, -5, 21101, 0, 1, -2, 22207, -5, -3, -1, 1206, -1, 2015,
-    21102, 1, 0, -2, 22202, -3, -2, -3, 22107, 0, -4, -1, 1206, -1, 2037, 22101, 0, -2, 1, 21101,
-    2037, 0, 0, 106, 0, 1912, 21202, -3, -1, -3, 22201, -5, -3, -5, 109, -6, 2105, 1, 0,
-];
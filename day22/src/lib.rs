@@ -0,0 +1,181 @@
+use common::affine::AffineMod;
+use common::runner::{Answer, Day, DefaultInput, Input};
+use common::ModularValue;
+use std::collections::VecDeque;
+
+type Deck = VecDeque<I>;
+
+const DECK_SIZE: I = 119315717514047;
+const REPETITIONS: I = 101741582076661;
+
+pub const DAY: Day = Day {
+    number: 22,
+    input: DefaultInput::File,
+    part1,
+    part2: Some(part2),
+};
+
+pub fn part1(input: &Input) -> Answer {
+    let deck = explicit_shuffle(input.text(), 10007);
+    deck.iter().position(|&x| x == 2019).unwrap().into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let shuffle = build_procedure(input.text(), DECK_SIZE);
+    apply_procedure_alot(shuffle, 2020, REPETITIONS)
+        .to_string()
+        .into()
+}
+
+type I = i64;
+
+/// Find the card at position `i` after shuffling `n_reps` times.
+fn apply_procedure_alot(shuffle: AffineMod<I>, i: I, n_reps: I) -> ModularValue<I> {
+    shuffle.pow(n_reps).inv().unwrap().apply(i)
+}
+
+/// Build the map from a card's position before the shuffle to its position after it.
+fn build_procedure(input: &str, deck_size: I) -> AffineMod<I> {
+    let mut shuffle = AffineMod::identity(deck_size);
+    for line in input.lines() {
+        let step = match () {
+            _ if line.starts_with("deal with") => {
+                let n: I = line.split_whitespace().last().unwrap().parse().unwrap();
+                AffineMod::scale(n, deck_size)
+            }
+            _ if line.starts_with("deal into") => AffineMod::new(-1, -1, deck_size),
+            _ if line.starts_with("cut") => {
+                let n: I = line.split_whitespace().last().unwrap().parse().unwrap();
+                AffineMod::offset(-n, deck_size)
+            }
+            _ => panic!("{}", line),
+        };
+        shuffle = shuffle.then(step);
+    }
+    shuffle
+}
+
+fn cut(mut deck: Deck, n: isize) -> Deck {
+    if n >= 0 {
+        for _ in 0..n {
+            let x = deck.pop_front().unwrap();
+            deck.push_back(x);
+        }
+    } else {
+        for _ in 0..-n {
+            let x = deck.pop_back().unwrap();
+            deck.push_front(x);
+        }
+    }
+    deck
+}
+
+fn deal_into_new_stack(deck: Deck) -> Deck {
+    deck.into_iter().rev().collect()
+}
+
+fn deal_with_increment(mut deck: Deck, n: usize) -> Deck {
+    let mut output = VecDeque::from(vec![-1; deck.len()]);
+    let mut i = 0;
+    while let Some(x) = deck.pop_front() {
+        output[i] = x;
+        i = (i + n) % output.len();
+    }
+    output
+}
+
+fn explicit_shuffle(input: &str, deck_size: I) -> Deck {
+    let mut deck: Deck = (0..deck_size).collect();
+
+    for line in input.lines() {
+        match () {
+            _ if line.starts_with("deal with increment") => {
+                let n = line.split_whitespace().last().unwrap().parse().unwrap();
+                deck = deal_with_increment(deck, n);
+            }
+            _ if line.starts_with("deal into new stack") => {
+                deck = deal_into_new_stack(deck);
+            }
+            _ if line.starts_with("cut") => {
+                let n = line.split_whitespace().last().unwrap().parse().unwrap();
+                deck = cut(deck, n);
+            }
+            _ => panic!("{}", line),
+        }
+    }
+    deck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deal_into() {
+        let deck_size: I = 7;
+        let input = "deal into new stack";
+
+        let deck: Vec<_> = explicit_shuffle(input, deck_size).into();
+
+        let shuffle = build_procedure(input, deck_size);
+
+        let mut idx = vec![];
+        for i in 0..deck_size {
+            idx.push(apply_procedure_alot(shuffle, i, 1));
+        }
+
+        assert_eq!(idx, deck);
+    }
+
+    #[test]
+    fn test_deal_with() {
+        let deck_size: I = 11;
+        let input = "deal with increment 5";
+
+        let deck: Vec<_> = explicit_shuffle(input, deck_size).into();
+
+        let shuffle = build_procedure(input, deck_size);
+
+        let mut idx = vec![];
+        for i in 0..deck_size {
+            idx.push(apply_procedure_alot(shuffle, i, 1));
+        }
+
+        assert_eq!(idx, deck);
+    }
+
+    #[test]
+    fn test_cut() {
+        let deck_size: I = 11;
+        let input = "cut -3";
+
+        let deck: Vec<_> = explicit_shuffle(input, deck_size).into();
+
+        let shuffle = build_procedure(input, deck_size);
+
+        let mut idx = vec![];
+        for i in 0..deck_size {
+            idx.push(apply_procedure_alot(shuffle, i, 1));
+        }
+
+        assert_eq!(idx, deck);
+    }
+
+    #[test]
+    fn test_repeated_shuffle() {
+        let deck_size: I = 10;
+        let input = "deal into new stack\ncut -2\ndeal with increment 7\ncut 8\ncut -4\ndeal with increment 7\ncut 3\ndeal with increment 9\ndeal with increment 3\ncut -1";
+
+        let shuffle = build_procedure(input, deck_size);
+
+        for n_reps in 1..5 {
+            let repeated = vec![input; n_reps as usize].join("\n");
+            let deck: Vec<_> = explicit_shuffle(&repeated, deck_size).into();
+
+            let idx: Vec<_> = (0..deck_size)
+                .map(|i| apply_procedure_alot(shuffle, i, n_reps))
+                .collect();
+            assert_eq!(idx, deck);
+        }
+    }
+}
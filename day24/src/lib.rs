@@ -0,0 +1,237 @@
+use common::cycle::brent;
+use common::grid::{Direction, Grid, Pos};
+use common::runner::{Answer, Day, DefaultInput, Input};
+
+pub const DAY: Day = Day {
+    number: 24,
+    input: DefaultInput::Text(INPUT),
+    part1,
+    part2: Some(part2),
+};
+
+/// The biodiversity of the first layout that appears twice.
+pub fn part1(input: &Input) -> Answer {
+    let field = Field::new(input.text());
+    let step = |field: &Field| {
+        let mut next = Field::empty();
+        simstep(field, &mut next);
+        next
+    };
+    let cycle = brent(&field, step);
+    cycle
+        .extrapolate(&field, step, cycle.tail)
+        .biodiversity()
+        .into()
+}
+
+pub fn part2(input: &Input) -> Answer {
+    let field = &mut Field2::new(input.text());
+    let back = &mut Field2::empty();
+
+    for _ in 0..200 {
+        simstep2(field, back);
+        std::mem::swap(field, back);
+    }
+
+    field.count_bugs().into()
+}
+
+fn simstep2(field: &Field2, back: &mut Field2) {
+    for k in 0..N as isize {
+        for p in field.data[k as usize].positions() {
+            if p == CENTER {
+                continue;
+            }
+
+            let n_neighbors = field.count_negighbors(p, k);
+
+            match (field.get(p, k).unwrap(), n_neighbors) {
+                ('#', 1) => back.set(p, k, '#'),
+                ('#', _) => back.set(p, k, '.'),
+                ('.', 1) | ('.', 2) => {
+                    if k == 0 || k == N as isize - 1 {
+                        panic!("Field too shallow")
+                    }
+                    back.set(p, k, '#');
+                }
+                ('.', _) => back.set(p, k, '.'),
+                _ => panic!("invalid configuration"),
+            }
+        }
+    }
+}
+
+/// The recursive field; level `k + 1` sits in the center tile of level `k`.
+struct Field2 {
+    data: Vec<Grid<char>>,
+}
+
+const N: usize = 405;
+
+const CENTER: Pos = Pos { x: 2, y: 2 };
+
+impl Field2 {
+    fn empty() -> Self {
+        Field2 {
+            data: vec![Field::empty().grid; N],
+        }
+    }
+
+    fn new(input: &str) -> Self {
+        let mut field = Self::empty();
+        field.data[N / 2] = Field::new(input).grid;
+        field
+    }
+
+    fn get(&self, p: Pos, k: isize) -> Option<char> {
+        self.data[k as usize].get(p).copied()
+    }
+
+    fn set(&mut self, p: Pos, k: isize, ch: char) {
+        self.data[k as usize][p] = ch;
+    }
+
+    fn count_bugs(&self) -> usize {
+        self.data
+            .iter()
+            .map(|f| f.iter().filter(|(_, ch)| **ch == '#').count())
+            .sum()
+    }
+
+    fn count_negighbors(&self, p: Pos, k: isize) -> usize {
+        Self::neighbors(p, k)
+            .filter_map(|(n, z)| self.get(n, z))
+            .filter(|&ch| ch == '#')
+            .count()
+    }
+
+    fn neighbors(p: Pos, k: isize) -> impl Iterator<Item = (Pos, isize)> {
+        assert_ne!(p, CENTER, "invalid field {:?}", p);
+        Direction::ALL
+            .iter()
+            .flat_map(move |&d| {
+                let n = p + d;
+                if n == CENTER {
+                    // the edge of the inner level that faces `p`
+                    let edge = CENTER + -d + -d;
+                    (0..5)
+                        .map(|i| match d {
+                            Direction::North | Direction::South => Pos::new(i, edge.y),
+                            Direction::West | Direction::East => Pos::new(edge.x, i),
+                        })
+                        .map(|n| (n, k + 1))
+                        .collect()
+                } else if n.x < 0 || n.y < 0 || n.x > 4 || n.y > 4 {
+                    vec![(CENTER + d, k - 1)]
+                } else {
+                    vec![(n, k)]
+                }
+            })
+            .filter(|&(_, z)| z >= 0 && z < N as isize)
+    }
+}
+
+fn simstep(field: &Field, back: &mut Field) {
+    for p in field.grid.positions() {
+        let n_neighbors = field
+            .grid
+            .neighbors(p)
+            .filter(|&n| field.grid[n] == '#')
+            .count();
+        match (field.grid[p], n_neighbors) {
+            ('#', 1) => back.grid[p] = '#',
+            ('#', _) => back.grid[p] = '.',
+            ('.', 1) | ('.', 2) => back.grid[p] = '#',
+            ('.', _) => back.grid[p] = '.',
+            _ => panic!("invalid configuration"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Field {
+    grid: Grid<char>,
+}
+
+impl Field {
+    fn empty() -> Self {
+        Field {
+            grid: Grid::new(5, 5, '.'),
+        }
+    }
+
+    fn new(input: &str) -> Self {
+        let grid = Grid::parse_chars(input).unwrap();
+        assert_eq!((grid.width(), grid.height()), (5, 5));
+        Field { grid }
+    }
+
+    fn biodiversity(&self) -> usize {
+        (0..25)
+            .map(|i| 2usize.pow(i))
+            .zip(self.grid.iter())
+            .filter(|(_, (_, ch))| **ch == '#')
+            .map(|(p, _)| p)
+            .sum()
+    }
+}
+
+const INPUT: &str = "###..
+.##..
+#....
+##..#
+.###.";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example24_1() {
+        let input = "....#
+#..#.
+#..##
+..#..
+#....";
+
+        let field = &mut Field2::new(input);
+        let back = &mut Field2::empty();
+
+        for _ in 0..10 {
+            simstep2(field, back);
+            std::mem::swap(field, back);
+        }
+
+        print!("{}", field.data[N / 2 - 1]);
+    }
+
+    #[test]
+    fn example24_2() {
+        let input = ".....
+..#..
+.....
+.....
+.....";
+
+        let field = &mut Field2::new(input);
+        let back = &mut Field2::empty();
+
+        println!(
+            "{:?}",
+            Field2::neighbors(Pos::new(3, 2), 10).collect::<Vec<_>>()
+        );
+
+        let top = Pos::new(2, 0);
+        println!("{}", field.count_negighbors(top, N as isize / 2));
+        println!("{}", field.count_negighbors(top, N as isize / 2 - 1));
+        println!("{}", field.count_negighbors(top, N as isize / 2 + 1));
+
+        simstep2(field, back);
+        std::mem::swap(field, back);
+
+        for dk in &[-1, 0, 1] {
+            let k = N as isize / 2 + dk;
+            println!("{}", field.data[k as usize]);
+        }
+    }
+}